@@ -1,6 +1,7 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, String, Vec,
-                  symbol_short, Error, Symbol};
+#![allow(clippy::too_many_arguments)] // #[contractimpl] expands the ten-parameter constructor outside the impl
+use soroban_sdk::{contract, contractclient, contractimpl, contracttype, token, Address, BytesN,
+                  Env, IntoVal, Map, String, Topics, Val, Vec, symbol_short, Error, Symbol};
 
 // TTL constants (industry standard values)
 // ~12 ledgers per minute, ~17280 ledgers per day
@@ -13,7 +14,31 @@ const PERSISTENT_TTL_EXTEND: u32 = 1_589_760;   // ~92 days
 const METADATA_KEY: Symbol = symbol_short!("METADATA");
 const CONFIG_KEY: Symbol = symbol_short!("CONFIG");
 const ADMINS_KEY: Symbol = symbol_short!("ADMINS");
-const USDC_BAL_KEY: Symbol = symbol_short!("USDC_BAL");
+const PRICERS_KEY: Symbol = symbol_short!("PRICERS");
+const PRICE_CNT_KEY: Symbol = symbol_short!("PRICE_CNT");
+const PRICE_MAX_KEY: Symbol = symbol_short!("PRICE_MAX");
+const ORACLE_KEY: Symbol = symbol_short!("ORACLE");
+const PAY_ASSETS_KEY: Symbol = symbol_short!("PAYASSETS");
+const OFFERING_KEY: Symbol = symbol_short!("OFFERING");
+const FEE_CFG_KEY: Symbol = symbol_short!("FEE_CFG");
+const WD_POLICY_KEY: Symbol = symbol_short!("WD_POLICY");
+const WD_HIST_KEY: Symbol = symbol_short!("WD_HIST");
+const WD_QUEUE_KEY: Symbol = symbol_short!("WD_QUEUE");
+const GUARDIAN_KEY: Symbol = symbol_short!("GUARDIAN");
+const HOLDER_CNT_KEY: Symbol = symbol_short!("HOLDERCNT");
+const AUDIT_CNT_KEY: Symbol = symbol_short!("AUDIT_CNT");
+const DOCUMENTS_KEY: Symbol = symbol_short!("DOCUMENTS");
+const TERMS_KEY: Symbol = symbol_short!("TERMS");
+const META_LOCKS_KEY: Symbol = symbol_short!("METALOCKS");
+const PARTITIONS_KEY: Symbol = symbol_short!("PARTITION");
+const DEFAULT_PARTITION: Symbol = symbol_short!("default");
+const WIND_DOWN_KEY: Symbol = symbol_short!("WINDDOWN");
+const DEBT_KEY: Symbol = symbol_short!("DEBT");
+const COUPON_KEY: Symbol = symbol_short!("COUPON");
+const SPLITS_KEY: Symbol = symbol_short!("SPLITS");
+const SPLIT_POOL_KEY: Symbol = symbol_short!("SPLITPOOL");
+const MIGRATION_KEY: Symbol = symbol_short!("MIGRATION");
+const COMM_RATES_KEY: Symbol = symbol_short!("COMMRATES");
 
 // Business logic constants
 const MAX_DECIMALS: u32 = 7;
@@ -24,6 +49,30 @@ const MAX_USDC_PRICE: i128 = 1_000_000_000_000; // 1 trillion
 const MAX_NAME_LEN: u32 = 64;
 const MAX_SYMBOL_LEN: u32 = 12;
 const MAX_HOME_DOMAIN_LEN: u32 = 256;
+const BPS_DENOMINATOR: i128 = 10_000;
+const MAX_PAGE_SIZE: u32 = 100;
+const MAX_SPLIT_RECIPIENTS: u32 = 10;
+const TX_WRITE_ENTRY_LIMIT: u32 = 50; // Network limit on ledger entries written per transaction
+const TX_FIXED_WRITE_ENTRIES: u32 = 2; // Contract instance and the caller's auth nonce
+const ONBOARDING_WRITES_PER_RECORD: u32 = 6; // KYC, compliance, jurisdiction and 3 audit entries
+const MAX_ONBOARDING_BATCH_SIZE: u32 =
+    (TX_WRITE_ENTRY_LIMIT - TX_FIXED_WRITE_ENTRIES) / ONBOARDING_WRITES_PER_RECORD;
+const EVENT_SCHEMA_VERSION: u32 = 2; // Baseline events were version 1; layouts are in CONTRACT_README.md
+const TRANSFER_WRITES_PER_RECIPIENT: u32 = 5; // Balance, holder index pair, coupon account, split epoch
+const TRANSFER_SENDER_WRITES: u32 = 7; // Balance, swap-removal from the holder index, coupon, epoch
+const MAX_TRANSFER_BATCH_SIZE: u32 = (TX_WRITE_ENTRY_LIMIT - TX_FIXED_WRITE_ENTRIES - TRANSFER_SENDER_WRITES)
+    / TRANSFER_WRITES_PER_RECIPIENT;
+const MIGRATION_WRITES_PER_HOLDER: u32 = 15; // 7 as for a sender, 3 audit entries, 5 on the successor
+const MAX_MIGRATION_BATCH_SIZE: u32 =
+    (TX_WRITE_ENTRY_LIMIT - TX_FIXED_WRITE_ENTRIES) / MIGRATION_WRITES_PER_HOLDER;
+const MIGRATION_TIMELOCK_LEDGERS: u32 = 120_960; // ~7 days for the guardian to cancel
+const MAX_DOCUMENT_URI_LEN: u32 = 256;
+const MAX_DOCUMENTS: u32 = 50;
+const MAX_PARTITIONS: u32 = 10;
+const SECONDS_PER_YEAR: i128 = 31_536_000;
+const COUPON_INDEX_SCALE: i128 = 1_000_000_000; // Precision of the per-unit coupon index
+const MAX_SPLITS: u32 = 20; // Bounds the lazy conversion applied on balance reads
+const WITHDRAWAL_BUCKETS: u32 = 20; // Withdrawal history is kept in this many buckets per window
 
 // Error codes
 const ERR_INVALID_AMOUNT: u32 = 1;
@@ -52,6 +101,84 @@ const ERR_NOT_ISSUER: u32 = 26;
 const ERR_CANNOT_REMOVE_ISSUER: u32 = 27;
 const ERR_NOT_AN_ADMIN: u32 = 28;
 const ERR_NOT_ADMIN_TTL: u32 = 29;
+const ERR_NOT_PRICER: u32 = 30;
+const ERR_INVALID_PRICE: u32 = 31;
+const ERR_PRICE_CHANGE_TOO_LARGE: u32 = 32;
+const ERR_INVALID_BPS: u32 = 33;
+const ERR_DUPLICATE_PRICER: u32 = 34;
+const ERR_NOT_A_PRICER: u32 = 35;
+const ERR_ORACLE_PRICE_STALE: u32 = 36;
+const ERR_ORACLE_PRICE_UNAVAILABLE: u32 = 37;
+const ERR_INVALID_ORACLE_CONFIG: u32 = 38;
+const ERR_ASSET_NOT_ACCEPTED: u32 = 39;
+const ERR_INVALID_PAYMENT_ASSET: u32 = 40;
+const ERR_NOT_ADMIN_PAYMENT_ASSET: u32 = 41;
+const ERR_INVALID_OFFERING: u32 = 42;
+const ERR_OFFERING_NOT_OPEN: u32 = 43;
+const ERR_HARD_CAP_EXCEEDED: u32 = 44;
+const ERR_OFFERING_NOT_SETTLED: u32 = 45;
+const ERR_USDC_IN_ESCROW: u32 = 46;
+const ERR_REFUND_NOT_AVAILABLE: u32 = 47;
+const ERR_NOTHING_TO_REFUND: u32 = 48;
+const ERR_OFFERING_USDC_ONLY: u32 = 49;
+const ERR_SLIPPAGE_EXCEEDED: u32 = 50;
+const ERR_NOT_ADMIN_INVESTOR: u32 = 51;
+const ERR_INVALID_LIMITS: u32 = 52;
+const ERR_BELOW_MIN_INVESTMENT: u32 = 53;
+const ERR_ABOVE_MAX_INVESTMENT: u32 = 54;
+const ERR_INVESTOR_CAP_EXCEEDED: u32 = 55;
+const ERR_LIMITS_USDC_ONLY: u32 = 56;
+const ERR_NOT_ADMIN_FEE: u32 = 57;
+const ERR_NOT_FEE_RECIPIENT: u32 = 58;
+const ERR_NOT_ADMIN_BROKER: u32 = 59;
+const ERR_NOT_APPROVED_BROKER: u32 = 60;
+const ERR_SELF_REFERRAL: u32 = 61;
+const ERR_NOTHING_TO_CLAIM: u32 = 62;
+const ERR_DESTINATION_NOT_ALLOWED: u32 = 63;
+const ERR_INVALID_SPLIT: u32 = 64;
+const ERR_NOT_GUARDIAN: u32 = 65;
+const ERR_INVALID_WITHDRAWAL_POLICY: u32 = 66;
+const ERR_WITHDRAWAL_CAP_EXCEEDED: u32 = 67;
+const ERR_WITHDRAWAL_REQUIRES_QUEUE: u32 = 68;
+const ERR_WITHDRAWAL_NOT_FOUND: u32 = 69;
+const ERR_WITHDRAWAL_TIMELOCKED: u32 = 70;
+const ERR_NOT_ADMIN_TREASURY: u32 = 71;
+const ERR_NOTHING_TO_RECONCILE: u32 = 72;
+const ERR_CANNOT_SWEEP_USDC: u32 = 73;
+const ERR_INVALID_BATCH: u32 = 74;
+const ERR_NOT_ADMIN_DOCUMENT: u32 = 75;
+const ERR_INVALID_DOCUMENT: u32 = 76;
+const ERR_DOCUMENT_NOT_FOUND: u32 = 77;
+const ERR_NOT_ADMIN_TERMS: u32 = 78;
+const ERR_TERMS_NOT_ACCEPTED: u32 = 79;
+const ERR_INVALID_METADATA: u32 = 80;
+const ERR_METADATA_LOCKED: u32 = 81;
+const ERR_NOT_ADMIN_PARTITION: u32 = 82;
+const ERR_INVALID_PARTITION: u32 = 83;
+const ERR_INSUFFICIENT_PARTITION_BALANCE: u32 = 84;
+const ERR_PARTITION_LOCKED: u32 = 85;
+const ERR_PARTITION_CLASS_NOT_ALLOWED: u32 = 86;
+const ERR_NOT_ADMIN_WIND_DOWN: u32 = 87;
+const ERR_WOUND_DOWN: u32 = 88;
+const ERR_NOT_WOUND_DOWN: u32 = 89;
+const ERR_NOTHING_TO_REDEEM: u32 = 90;
+const ERR_NOT_ADMIN_DEBT: u32 = 91;
+const ERR_INVALID_DEBT_TERMS: u32 = 92;
+const ERR_NOT_DEBT_TOKEN: u32 = 93;
+const ERR_COUPON_NOT_DUE: u32 = 94;
+const ERR_NOT_MATURED: u32 = 95;
+const ERR_NOT_ADMIN_SPLIT: u32 = 96;
+const ERR_INVALID_SPLIT_RATIO: u32 = 97;
+const ERR_NOT_ADMIN_MIGRATION: u32 = 98;
+const ERR_INVALID_MIGRATION: u32 = 99;
+const ERR_MIGRATING: u32 = 100;
+const ERR_NOT_MIGRATING: u32 = 101;
+const ERR_NOTHING_TO_MIGRATE: u32 = 102;
+const ERR_ALLOCATION_LOCKED: u32 = 103;
+const ERR_POLICY_REQUIRES_GUARDIAN: u32 = 104;
+const ERR_MIGRATION_TIMELOCKED: u32 = 105;
+const ERR_MIGRATION_UNDERWAY: u32 = 106;
+const ERR_COUPON_UNFUNDED: u32 = 107;
 
 // Define token metadata structure
 #[contracttype]
@@ -67,6 +194,15 @@ pub struct TokenMetadata {
     pub usdc_token: Address, // USDC token contract address
 }
 
+// Define the mutable token metadata fields
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetadataField {
+    Name,
+    Symbol,
+    HomeDomain,
+}
+
 // Define contract configuration
 #[contracttype]
 #[derive(Clone)]
@@ -86,6 +222,307 @@ pub enum ComplianceStatus {
     Suspended,
 }
 
+// Define a recorded price change
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceChange {
+    pub previous_price: i128,
+    pub price: i128,
+    pub timestamp: u64,
+    pub updated_by: Address,
+}
+
+// SEP-40 asset identifier
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum OracleAsset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+// SEP-40 price record
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+// Define what purchase does when the oracle price is stale or missing
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum StalePriceAction {
+    UseStaticPrice,
+    Halt,
+}
+
+// Define oracle pricing configuration
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OracleConfig {
+    pub oracle: Address, // SEP-40 price feed contract address
+    pub asset: OracleAsset, // Asset to query on the feed
+    pub max_age: u64, // Maximum price age in seconds
+    pub on_stale: StalePriceAction,
+}
+
+// Subset of the SEP-40 price feed interface used for pricing
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    fn decimals(env: Env) -> u32;
+    fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData>;
+}
+
+// Interface a successor token contract exposes to receive migrated balances.
+// The successor should only accept calls from this contract.
+#[contractclient(name = "SuccessorTokenClient")]
+pub trait SuccessorToken {
+    fn migrate_mint(env: Env, to: Address, amount: i128, kyc_verified: bool, compliance: ComplianceStatus);
+}
+
+// Define pricing for an accepted non-USDC payment asset
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaymentAsset {
+    pub price: i128, // Price per token in the asset's smallest unit
+    pub decimals: u32, // Decimals of the asset contract
+}
+
+// Define when platform fees are paid out
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum FeeMode {
+    Immediate, // Paid to the recipient at purchase time
+    Accrued, // Held by the contract until the recipient withdraws
+}
+
+// Define platform fee configuration
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeConfig {
+    pub recipient: Address,
+    pub fee_bps: u32,
+    pub mode: FeeMode,
+}
+
+// Define an approved broker-dealer
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrokerInfo {
+    pub commission_bps: u32,
+    pub approved: bool,
+}
+
+// Define a broker's referred sales and commissions for one payment asset
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrokerSales {
+    pub volume: i128, // Payment amount referred
+    pub tokens_sold: i128,
+    pub accrued: i128, // Unclaimed commission
+    pub claimed: i128,
+}
+
+// Define limits on USDC withdrawals (0 disables a limit)
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithdrawalPolicy {
+    pub period_ledgers: u32, // Length of the rolling window
+    pub period_cap: i128, // Maximum USDC withdrawn within the window
+    pub timelock_threshold: i128, // Withdrawals above this must be queued
+    pub timelock_ledgers: u32, // Delay before a queued withdrawal can execute
+}
+
+// Define a queued USDC withdrawal
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedWithdrawal {
+    pub destination: Address,
+    pub amount: i128,
+    pub executable_at: u32, // Ledger sequence
+}
+
+// Define a comparison of tracked asset liabilities with the actual contract balance
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reconciliation {
+    pub tracked: i128, // Issuer balance + accrued fees + unclaimed commissions
+    pub actual: i128, // Balance reported by the asset contract
+    pub surplus: i128, // actual - tracked (negative on a shortfall)
+}
+
+// Define the transfer rules for a token partition (share class or tranche)
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartitionRules {
+    pub locked_until: u64, // Timestamp before which the partition cannot be transferred
+    pub allowed_classes: Vec<InvestorClass>, // Recipient classes allowed (empty = any)
+}
+
+// Define the final liquidation state after the underlying property is sold
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindDown {
+    pub proceeds: i128, // USDC deposited for distribution
+    pub supply: i128, // Total supply when wind-down started
+    pub redeemed: i128, // Tokens burned through redeem_final
+    pub paid: i128, // USDC paid out through redeem_final
+    pub started_at: u64,
+}
+
+// Define the terms of a debt-style token (note)
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DebtTerms {
+    pub face_value: i128, // USDC principal per whole token
+    pub coupon_bps: u32, // Annual coupon rate in basis points
+    pub period: u64, // Seconds between coupon payment dates
+    pub first_payment: u64, // Timestamp of the first coupon payment date
+    pub maturity: u64, // Timestamp when principal is repaid
+}
+
+// Define the running coupon state of a debt-style token
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CouponState {
+    pub index: i128, // Cumulative coupon per token unit, scaled by COUPON_INDEX_SCALE
+    pub payments: u32, // Coupon payment dates accrued to holders so far
+    pub due: i128, // USDC owed to holders for those dates
+    pub funded: i128, // USDC deposited for coupons
+    pub claimed: i128, // USDC claimed by holders
+}
+
+// Define a holder's coupon checkpoint
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CouponAccount {
+    pub index: i128, // Coupon index at the last settlement
+    pub accrued: i128, // USDC coupon settled but not yet claimed
+}
+
+// Define a split or reverse split: every `denominator` units become `numerator` units
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StockSplit {
+    pub numerator: u32,
+    pub denominator: u32,
+    pub timestamp: u64,
+}
+
+// Define a migration to a successor token contract
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Migration {
+    pub successor: Address,
+    pub numerator: u32, // Successor units issued per `denominator` units burned
+    pub denominator: u32,
+    pub supply: i128, // Total supply when the migration started
+    pub migrated: i128, // Units burned here
+    pub issued: i128, // Units minted on the successor
+    pub started_at: u64,
+    pub executable_at: u32, // Ledger sequence from which holders can migrate
+}
+
+// Define an investor onboarding record for batch KYC and compliance updates
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OnboardingRecord {
+    pub address: Address,
+    pub kyc_verified: bool,
+    pub compliance: ComplianceStatus,
+    pub jurisdiction: Symbol, // ISO 3166-1 alpha-2 country code
+}
+
+// Define a holder's cap table entry
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct HolderInfo {
+    pub address: Address,
+    pub balance: i128,
+    pub kyc_verified: bool,
+    pub compliance: ComplianceStatus,
+    pub frozen: bool, // Compliance status is Suspended
+}
+
+// Define the justification attached to an admin action
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Justification {
+    pub reason_code: u32,
+    pub document_hash: BytesN<32>, // e.g. hash of a court order or KYC case file
+}
+
+// Define an entry in the admin audit log
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditEntry {
+    pub action: Symbol,
+    pub caller: Address,
+    pub subject: Option<Address>,
+    pub reason_code: Option<u32>,
+    pub document_hash: Option<BytesN<32>>,
+    pub timestamp: u64,
+}
+
+// Define a legal document tied to the token
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Document {
+    pub uri: String,
+    pub document_hash: BytesN<32>,
+    pub updated_at: u64,
+}
+
+// Define an investor's acceptance of a subscription agreement version
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TermsAcceptance {
+    pub document_hash: BytesN<32>,
+    pub accepted_at: u64,
+}
+
+// Define investor classification enum
+#[contracttype]
+#[derive(Clone, PartialEq, Debug)]
+pub enum InvestorClass {
+    Retail,
+    Accredited,
+    Institutional,
+}
+
+// Define investment limits in USDC for an investor class (0 means no limit)
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvestmentLimits {
+    pub min_purchase: i128,
+    pub max_purchase: i128,
+    pub max_total: i128, // Cumulative maximum per beneficiary
+}
+
+// Define a primary offering window with funding targets in USDC
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Offering {
+    pub id: u32,
+    pub start: u64, // Ledger timestamp the offering opens at
+    pub end: u64, // Ledger timestamp the offering closes at
+    pub soft_cap: i128, // Minimum raise before USDC leaves escrow
+    pub hard_cap: i128, // Maximum raise
+    pub raised: i128,
+    pub fees: i128, // Platform fees charged on escrowed contributions
+    pub commissions: i128, // Broker commissions charged on escrowed contributions
+}
+
+// Define a buyer's position in an offering for tokens allocated to one beneficiary
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OfferingPosition {
+    pub contributed: i128, // USDC paid by the buyer
+    pub allocated: i128, // Tokens received by the beneficiary
+    pub fees: i128, // Platform fees charged on the contributed USDC
+    pub referrals: Map<Address, BrokerSales>, // Referred sales by broker
+}
+
 // Storage key types for user-specific data
 #[contracttype]
 #[derive(Clone)]
@@ -93,22 +530,114 @@ pub enum DataKey {
     Balance(Address),
     KycVerified(Address),
     ComplianceStatus(Address),
+    PriceHistory(u32),
+    PaymentAsset(Address),
+    AssetBalance(Address),
+    OfferingPosition(u32, Address, Address), // offering id, buyer, beneficiary
+    OfferingAllocation(u32, Address), // Tokens locked for a beneficiary until the offering settles
+    InvestorClass(Address),
+    InvestmentLimits(InvestorClass),
+    TotalInvested(Address),
+    AccruedFees(Address, Address), // recipient, asset
+    TotalAccruedFees(Address), // asset
+    Broker(Address),
+    BrokerSales(Address, Address),
+    EscrowedCommission(u32, Address),
+    WithdrawalDestination(Address),
+    QueuedWithdrawal(u32),
+    UnclaimedCommissions(Address),
+    Jurisdiction(Address),
+    HolderIndex(u32),
+    HolderPosition(Address),
+    AuditEntry(u32),
+    SubjectAuditCount(Address),
+    SubjectAuditEntry(Address, u32), // subject, per-subject index -> audit log index
+    Document(Symbol),
+    TermsAcceptance(Address),
+    Partition(Symbol),
+    PartitionBalance(Address, Symbol),
+    PartitionedBalance(Address), // Total held outside the default partition
+    CouponAccount(Address),
+    SplitEpoch(Address), // Number of splits already applied to the stored balances
 }
 
 // Define event types that the contract will emit - using tuple variants
 #[contracttype]
 pub enum SecurityTokenEvent {
     Init(TokenMetadata),
-    Transfer(Address, Address, i128), // from, to, amount
     ComplianceUpdated(Address, ComplianceStatus), // address, status
     KycVerified(Address, bool), // address, status
     AuthorizationChanged(bool, bool), // required, revocable
-    ClawbackExecuted(Address, i128), // from, amount
     Purchase(Address, Address, i128, i128), // buyer, beneficiary, token_amount, usdc_amount
     UsdcWithdrawn(Address, i128), // admin, amount
     AdminAdded(Address, Address), // admin, new_admin
     AdminRemoved(Address, Address), // issuer, removed_admin
-    TransferRestrictionChanged(bool), // restricted status
+    TransferRestrictionChanged(Address, bool), // updated_by, restricted status
+    PriceUpdated(Address, i128, i128), // updated_by, previous_price, new_price
+    PricerAdded(Address, Address), // issuer, pricer
+    PricerRemoved(Address, Address), // issuer, pricer
+    MaxPriceChangeUpdated(u32), // max change in basis points
+    OracleUpdated(Address, Address), // updated_by, oracle
+    OracleCleared(Address), // cleared_by
+    AssetPurchase(Address, Address, Address, i128, i128), // buyer, beneficiary, asset, token_amount, asset_amount
+    AssetWithdrawn(Address, Address, i128), // issuer, asset, amount
+    PaymentAssetSet(Address, i128, u32), // asset, price, decimals
+    PaymentAssetRemoved(Address), // asset
+    OfferingConfigured(u32, u64, u64, i128, i128), // id, start, end, soft_cap, hard_cap
+    OfferingCleared(u32), // id
+    RefundClaimed(Address, i128, i128), // buyer, usdc_amount, token_amount
+    InvestorClassUpdated(Address, InvestorClass), // address, class
+    InvestmentLimitsUpdated(InvestorClass, InvestmentLimits), // class, limits
+    FeeConfigUpdated(Address, u32), // recipient, fee_bps
+    FeeCharged(Address, Address, i128), // recipient, asset, fee
+    FeesWithdrawn(Address, Address, i128), // recipient, asset, amount
+    BrokerApproved(Address, u32), // broker, commission_bps
+    BrokerRevoked(Address), // broker
+    CommissionAccrued(Address, Address, i128), // broker, asset, commission
+    CommissionClaimed(Address, Address, i128), // broker, asset, amount
+    WithdrawalDestinationAdded(Address), // destination
+    WithdrawalDestinationRemoved(Address), // destination
+    UsdcWithdrawnTo(Address, Address, i128), // issuer, destination, amount
+    GuardianUpdated(Address, Address), // updated_by, guardian
+    WithdrawalPolicyUpdated(Address, WithdrawalPolicy), // updated_by, policy
+    WithdrawalQueued(u32, Address, i128, u32), // id, destination, amount, executable_at
+    WithdrawalExecuted(u32, Address, i128), // id, destination, amount
+    WithdrawalCancelled(u32, Address), // id, cancelled_by
+    UsdcReconciled(Address, i128), // reconciled_by, surplus credited to the issuer
+    TokenSwept(Address, Address, Address, i128), // swept_by, asset, to, amount
+    JurisdictionUpdated(Address, Symbol), // address, jurisdiction
+    InstanceTtlBumped(Address), // bumped_by
+    UserTtlsBumped(Address, u32), // bumped_by, address count
+    PartitionConfigured(Symbol, PartitionRules), // partition, rules
+    TransferByPartition(Symbol, Address, Address, i128), // partition, from, to, amount
+    PartitionChanged(Address, Symbol, Symbol, i128), // holder, from_partition, to_partition, amount
+}
+
+// Define events for legal documents, subscription terms and metadata changes
+#[contracttype]
+pub enum DisclosureEvent {
+    DocumentUpdated(Symbol, String, BytesN<32>), // name, uri, document_hash
+    DocumentRemoved(Symbol), // name
+    ActiveTermsSet(Address, BytesN<32>), // updated_by, document_hash
+    ActiveTermsCleared(Address), // cleared_by
+    TermsAccepted(Address, BytesN<32>), // investor, document_hash
+    MetadataUpdated(MetadataField, String, String), // field, previous, new
+    MetadataFieldLocked(MetadataField), // field
+}
+
+// Define events for lifecycle actions such as the final wind-down
+#[contracttype]
+pub enum LifecycleEvent {
+    WindDownStarted(Address, i128, i128), // started_by, proceeds, supply
+    FinalRedemption(Address, i128, i128), // holder, tokens burned, usdc paid
+    SupplyRetired(i128), // total usdc paid
+    DebtTermsSet(DebtTerms), // terms
+    CouponFunded(u32, i128), // payment dates covered so far, usdc amount
+    CouponClaimed(Address, i128), // holder, usdc amount
+    Split(u32, u32, i128, i128), // numerator, denominator, new total_supply, new usdc_price
+    MigrationStarted(Address, u32, u32, i128), // successor, numerator, denominator, supply
+    MigrationCancelled(Address, Address), // cancelled_by, successor
+    Migrated(Address, i128, i128), // holder, units burned, successor units issued
 }
 
 // Main contract
@@ -202,18 +731,14 @@ impl SecurityTokenContract {
         admins.push_back(issuer.clone());
         env.storage().instance().set(&ADMINS_KEY, &admins);
 
-        // Initialize USDC balance in INSTANCE storage
-        env.storage().instance().set(&USDC_BAL_KEY, &INITIAL_BALANCE);
+        // Record the initial price as the first price history entry
+        Self::record_price_change(&env, &admin, 0, usdc_price);
 
         // Assign total supply to issuer in PERSISTENT storage (user-specific data)
-        let issuer_balance_key = DataKey::Balance(issuer.clone());
-        env.storage()
-            .persistent()
-            .set(&issuer_balance_key, &total_supply);
+        Self::write_balance(&env, &issuer, total_supply).expect("Failed to write initial balance");
 
         // Extend TTLs for all storage entries
         Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, &issuer_balance_key);
 
         // Auto-approve issuer for KYC and compliance since they're the token creator
         env.storage()
@@ -223,9 +748,11 @@ impl SecurityTokenContract {
             .persistent()
             .set(&DataKey::ComplianceStatus(issuer.clone()), &ComplianceStatus::Approved);
 
-        // Emit initialization event
-        env.events().publish(
-            (symbol_short!("init"),),
+        // Emit initialization event and SEP-41 mint of the initial supply
+        Self::emit_mint(&env, &issuer, total_supply);
+        Self::publish_event(
+            &env,
+            (symbol_short!("init"), issuer.clone()),
             SecurityTokenEvent::Init(metadata),
         );
     }
@@ -234,6 +761,9 @@ impl SecurityTokenContract {
     pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), Error> {
         from.require_auth();
 
+        // Transfers stop permanently once the token is wound down
+        Self::require_active(&env)?;
+
         // Validate amount
         if amount <= 0 {
             return Err(Error::from_contract_error(ERR_INVALID_AMOUNT));
@@ -253,655 +783,4385 @@ impl SecurityTokenContract {
         // Check compliance requirements
         Self::check_compliance_requirements(&env, &config, &from, &to)?;
 
+        // Plain transfers only move unlocked units in the default partition
+        Self::require_unlocked(&env, &from, amount)?;
+
         // Execute the transfer
         Self::execute_transfer(&env, &from, &to, amount)?;
 
         // Emit transfer event
-        env.events().publish(
-            (symbol_short!("transfer"),),
-            SecurityTokenEvent::Transfer(from.clone(), to.clone(), amount),
-        );
+        Self::emit_transfer(&env, &from, &to, amount);
 
         Ok(())
     }
 
-    // Set KYC verification status for an address
-    pub fn set_kyc_status(
+    // Transfer tokens from one sender to many recipients. All transfers succeed or none do.
+    pub fn batch_transfer(
         env: Env,
-        caller: Address,
-        address: Address,
-        verified: bool,
+        from: Address,
+        transfers: Vec<(Address, i128)>,
     ) -> Result<(), Error> {
-        caller.require_auth();
+        from.require_auth();
 
-        // Check if caller is admin
-        if !Self::is_admin(&env, &caller) {
-            return Err(Error::from_contract_error(ERR_NOT_ADMIN_KYC));
+        // Transfers stop permanently once the token is wound down
+        Self::require_active(&env)?;
+
+        // Validate batch size
+        if transfers.is_empty() || transfers.len() > MAX_TRANSFER_BATCH_SIZE {
+            return Err(Error::from_contract_error(ERR_INVALID_BATCH));
         }
 
-        // Check if authorization is revocable when attempting to revoke
+        // Load config from instance storage
         let config = Self::get_config(&env);
-        if !config.authorization_revocable && !verified {
-            // Get current KYC status
-            let current_kyc: bool = env
-                .storage()
-                .persistent()
-                .get(&DataKey::KycVerified(address.clone()))
-                .unwrap_or(false);
-            
-            // If currently verified and trying to revoke, check if revocation is allowed
-            if current_kyc {
-                return Err(Error::from_contract_error(ERR_AUTHORIZATION_NOT_REVOCABLE));
+
+        // Check if transfers are currently allowed
+        if config.transfer_restricted {
+            // Only admins can transfer when restricted
+            if !Self::is_admin(&env, &from) {
+                return Err(Error::from_contract_error(ERR_TRANSFER_RESTRICTED));
             }
         }
 
-        // Update KYC status in PERSISTENT storage
-        let kyc_key = DataKey::KycVerified(address.clone());
-        env.storage()
-            .persistent()
-            .set(&kyc_key, &verified);
+        // Validate every transfer before moving any balance
+        let mut total: i128 = 0;
+        for (to, amount) in transfers.iter() {
+            if amount <= 0 {
+                return Err(Error::from_contract_error(ERR_INVALID_AMOUNT));
+            }
+            if to == from {
+                return Err(Error::from_contract_error(ERR_SELF_TRANSFER_NOT_ALLOWED));
+            }
+            Self::check_compliance_requirements(&env, &config, &from, &to)?;
+            total = total.checked_add(amount)
+                .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        }
 
-        // Extend TTL for the KYC entry
-        Self::extend_persistent_ttl(&env, &kyc_key);
+        // Debit the sender once, from the default partition
+        let from_balance = Self::balance(env.clone(), from.clone());
+        Self::require_unlocked(&env, &from, total)?;
+        Self::write_balance(&env, &from, from_balance - total)?;
 
-        // Emit event
-        env.events().publish(
-            (symbol_short!("kyc"),),
-            SecurityTokenEvent::KycVerified(address.clone(), verified),
+        // Credit each recipient and emit the individual transfer events
+        for (to, amount) in transfers.iter() {
+            let new_to_balance = Self::balance(env.clone(), to.clone())
+                .checked_add(amount)
+                .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+            Self::write_balance(&env, &to, new_to_balance)?;
+
+            Self::emit_transfer(&env, &from, &to, amount);
+        }
+
+        Ok(())
+    }
+
+    // Transfer tokens within a partition, applying the partition's rules
+    pub fn transfer_by_partition(
+        env: Env,
+        from: Address,
+        partition: Symbol,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        if partition == DEFAULT_PARTITION {
+            return Self::transfer(env, from, to, amount);
+        }
+
+        from.require_auth();
+
+        // Transfers stop permanently once the token is wound down
+        Self::require_active(&env)?;
+
+        // Validate amount
+        if amount <= 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_AMOUNT));
+        }
+
+        // Load config from instance storage
+        let config = Self::get_config(&env);
+
+        // Check if transfers are currently allowed
+        if config.transfer_restricted {
+            // Only admins can transfer when restricted
+            if !Self::is_admin(&env, &from) {
+                return Err(Error::from_contract_error(ERR_TRANSFER_RESTRICTED));
+            }
+        }
+
+        // Check compliance requirements
+        Self::check_compliance_requirements(&env, &config, &from, &to)?;
+
+        // Check partition rules
+        let rules = Self::partition_rules(env.clone(), partition.clone())
+            .ok_or(Error::from_contract_error(ERR_INVALID_PARTITION))?;
+        if env.ledger().timestamp() < rules.locked_until {
+            return Err(Error::from_contract_error(ERR_PARTITION_LOCKED));
+        }
+        if !rules.allowed_classes.is_empty()
+            && !rules.allowed_classes.contains(Self::investor_class(env.clone(), to.clone()))
+        {
+            return Err(Error::from_contract_error(ERR_PARTITION_CLASS_NOT_ALLOWED));
+        }
+
+        let from_partition_balance = Self::balance_of_by_partition(env.clone(), from.clone(), partition.clone());
+        if from_partition_balance < amount {
+            return Err(Error::from_contract_error(ERR_INSUFFICIENT_PARTITION_BALANCE));
+        }
+
+        // Execute the transfer and move the partition balances
+        Self::execute_transfer(&env, &from, &to, amount)?;
+        Self::set_partition_balance(&env, &from, &partition, from_partition_balance - amount);
+        let to_partition_balance = Self::balance_of_by_partition(env.clone(), to.clone(), partition.clone())
+            .checked_add(amount)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        Self::set_partition_balance(&env, &to, &partition, to_partition_balance);
+
+        // Emit transfer events
+        Self::emit_transfer(&env, &from, &to, amount);
+        Self::publish_event(
+            &env,
+            (symbol_short!("partxfer"), from.clone(), to.clone()),
+            SecurityTokenEvent::TransferByPartition(partition, from, to, amount),
         );
 
         Ok(())
     }
 
-    // Set compliance status for an address
-    pub fn set_compliance_status(
+    // Register a partition or update its rules
+    pub fn set_partition(
         env: Env,
         caller: Address,
-        address: Address,
-        status: ComplianceStatus,
+        partition: Symbol,
+        rules: PartitionRules,
+        justification: Option<Justification>,
     ) -> Result<(), Error> {
         caller.require_auth();
 
         // Check if caller is admin
         if !Self::is_admin(&env, &caller) {
-            return Err(Error::from_contract_error(ERR_NOT_ADMIN_COMPLIANCE));
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_PARTITION));
         }
 
-        // Check if authorization is revocable when attempting to downgrade from Approved
-        let config = Self::get_config(&env);
-        if !config.authorization_revocable && status != ComplianceStatus::Approved {
-            // Get current compliance status
-            let current_status: ComplianceStatus = env
-                .storage()
-                .persistent()
-                .get(&DataKey::ComplianceStatus(address.clone()))
-                .unwrap_or(ComplianceStatus::Pending);
-            
-            // If currently approved and trying to change to non-approved, check if revocation is allowed
-            if current_status == ComplianceStatus::Approved {
-                return Err(Error::from_contract_error(ERR_AUTHORIZATION_NOT_REVOCABLE));
+        // The default partition has no rules of its own
+        if partition == DEFAULT_PARTITION {
+            return Err(Error::from_contract_error(ERR_INVALID_PARTITION));
+        }
+
+        // Add to the partition list if new
+        let mut partitions = Self::partitions(env.clone());
+        if !partitions.contains(&partition) {
+            if partitions.len() >= MAX_PARTITIONS {
+                return Err(Error::from_contract_error(ERR_INVALID_PARTITION));
             }
+            partitions.push_back(partition.clone());
+            env.storage().instance().set(&PARTITIONS_KEY, &partitions);
         }
 
-        // Update compliance status in PERSISTENT storage
-        let compliance_key = DataKey::ComplianceStatus(address.clone());
-        env.storage()
-            .persistent()
-            .set(&compliance_key, &status);
+        // Store rules in PERSISTENT storage
+        let partition_key = DataKey::Partition(partition.clone());
+        env.storage().persistent().set(&partition_key, &rules);
 
-        // Extend TTL for the compliance entry
-        Self::extend_persistent_ttl(&env, &compliance_key);
+        // Extend TTLs
+        Self::extend_persistent_ttl(&env, &partition_key);
+        Self::extend_instance_ttl(&env);
 
         // Emit event
-        env.events().publish(
-            (symbol_short!("complianc"),),
-            SecurityTokenEvent::ComplianceUpdated(address.clone(), status),
+        Self::publish_event(
+            &env,
+            (symbol_short!("partition"), caller.clone()),
+            SecurityTokenEvent::PartitionConfigured(partition, rules),
         );
 
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("partition"), &caller, None, justification);
+
         Ok(())
     }
 
-    // Execute clawback of tokens (regulatory action)
-    pub fn clawback(
+    // Move a holder's units between partitions, e.g. to issue Class B units or unlock a tranche
+    pub fn change_partition(
         env: Env,
         caller: Address,
-        from: Address,
+        holder: Address,
+        from_partition: Symbol,
+        to_partition: Symbol,
         amount: i128,
+        justification: Option<Justification>,
     ) -> Result<(), Error> {
         caller.require_auth();
 
         // Check if caller is admin
         if !Self::is_admin(&env, &caller) {
-            return Err(Error::from_contract_error(ERR_NOT_ADMIN_CLAWBACK));
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_PARTITION));
         }
 
-        // Validate amount is positive
+        // Validate amount and partitions
         if amount <= 0 {
             return Err(Error::from_contract_error(ERR_INVALID_AMOUNT));
         }
+        if from_partition == to_partition {
+            return Err(Error::from_contract_error(ERR_INVALID_PARTITION));
+        }
+        for partition in [&from_partition, &to_partition] {
+            if *partition != DEFAULT_PARTITION
+                && Self::partition_rules(env.clone(), partition.clone()).is_none()
+            {
+                return Err(Error::from_contract_error(ERR_INVALID_PARTITION));
+            }
+        }
 
-        // Get current balance using helper
-        let balance_key = DataKey::Balance(from.clone());
-        let current_balance = Self::balance(env.clone(), from.clone());
+        let from_balance = Self::balance_of_by_partition(env.clone(), holder.clone(), from_partition.clone());
+        if from_balance < amount {
+            return Err(Error::from_contract_error(ERR_INSUFFICIENT_PARTITION_BALANCE));
+        }
+        if from_partition == DEFAULT_PARTITION {
+            Self::require_unlocked(&env, &holder, amount)?;
+        }
 
-        // Clawback the minimum of requested amount and available balance
-        // This ensures we take what's available rather than failing if exact amount isn't present
-        let actual_clawback_amount = if current_balance < amount {
-            current_balance
-        } else {
-            amount
-        };
+        // Default partition balance is implied by the total, so only named partitions are written
+        if from_partition != DEFAULT_PARTITION {
+            Self::set_partition_balance(&env, &holder, &from_partition, from_balance - amount);
+        }
+        if to_partition != DEFAULT_PARTITION {
+            let to_balance = Self::balance_of_by_partition(env.clone(), holder.clone(), to_partition.clone())
+                .checked_add(amount)
+                .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+            Self::set_partition_balance(&env, &holder, &to_partition, to_balance);
+        }
 
-        // Get issuer address from metadata
-        let metadata = Self::get_metadata(&env);
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("partchg"), caller.clone(), holder.clone()),
+            SecurityTokenEvent::PartitionChanged(holder.clone(), from_partition, to_partition, amount),
+        );
 
-        // Get issuer's current balance from PERSISTENT storage
-        let issuer_balance_key = DataKey::Balance(metadata.issuer.clone());
-        let issuer_balance: i128 = env
-            .storage()
-            .persistent()
-            .get(&issuer_balance_key)
-            .unwrap_or(INITIAL_BALANCE);
-
-        // Update balances in PERSISTENT storage
-        let new_balance = current_balance.checked_sub(actual_clawback_amount)
-            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("partchg"), &caller, Some(holder), justification);
 
-        let new_issuer_balance = issuer_balance.checked_add(actual_clawback_amount)
-            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+        Ok(())
+    }
 
-        env.storage()
-            .persistent()
-            .set(&balance_key, &new_balance);
+    // Start the final wind-down: deposit the property sale proceeds for pro-rata redemption.
+    // Transfers and purchases stop permanently.
+    pub fn start_wind_down(
+        env: Env,
+        caller: Address,
+        proceeds: i128,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
 
-        env.storage()
-            .persistent()
-            .set(&issuer_balance_key, &new_issuer_balance);
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_WIND_DOWN));
+        }
 
-        // Extend TTLs for the balance entries
-        Self::extend_persistent_ttl(&env, &balance_key);
-        Self::extend_persistent_ttl(&env, &issuer_balance_key);
+        Self::begin_wind_down(&env, &caller, proceeds)?;
 
-        // Emit event with actual clawed back amount
-        env.events().publish(
-            (symbol_short!("clawback"),),
-            SecurityTokenEvent::ClawbackExecuted(from.clone(), actual_clawback_amount),
-        );
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("winddown"), &caller, None, justification);
 
         Ok(())
     }
 
-    // Add an admin to the token (issuer only)
-    pub fn add_admin(env: Env, caller: Address, new_admin: Address) -> Result<(), Error> {
-        caller.require_auth();
+    // Burn the holder's entire balance for a pro-rata share of the sale proceeds
+    pub fn redeem_final(env: Env, holder: Address) -> Result<i128, Error> {
+        holder.require_auth();
 
-        // Check if caller is issuer (only issuer can add admins)
-        if !Self::is_issuer(&env, &caller) {
-            return Err(Error::from_contract_error(ERR_NOT_ADMIN_ADD_ADMIN));
-        }
+        let mut wind_down = Self::wind_down(env.clone())
+            .ok_or(Error::from_contract_error(ERR_NOT_WOUND_DOWN))?;
 
-        // Check if already an admin using helper
-        if Self::is_admin(&env, &new_admin) {
-            return Err(Error::from_contract_error(ERR_DUPLICATE_ADMIN));
+        let balance = Self::balance(env.clone(), holder.clone());
+        if balance <= 0 {
+            return Err(Error::from_contract_error(ERR_NOTHING_TO_REDEEM));
         }
 
-        // Get current admin list from INSTANCE storage
-        let mut admins: Vec<Address> = env
-            .storage()
-            .instance()
-            .get(&ADMINS_KEY)
-            .unwrap();
+        // Burn the holder's tokens, including any partitioned units
+        let partitioned = balance - Self::default_balance(&env, &holder);
+        Self::drain_partitions(&env, &holder, partitioned);
+        Self::write_balance(&env, &holder, 0)?;
 
-        // Add to admin list
-        admins.push_back(new_admin.clone());
-        env.storage().instance().set(&ADMINS_KEY, &admins);
+        // Pro-rata share of the remaining proceeds, rounded down; the last holder receives the rest
+        let mut metadata = Self::get_metadata(&env);
+        let payout = balance.checked_mul(wind_down.proceeds - wind_down.paid)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+            .checked_div(metadata.total_supply)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        metadata.total_supply -= balance;
+        env.storage().instance().set(&METADATA_KEY, &metadata);
+
+        wind_down.redeemed += balance;
+        wind_down.paid += payout;
+        env.storage().instance().set(&WIND_DOWN_KEY, &wind_down);
+
+        // Transfer payout from contract to holder with balance verification
+        if payout > 0 {
+            Self::transfer_out(&env, &metadata.usdc_token, &holder, payout)?;
+        }
 
         // Extend instance TTL
         Self::extend_instance_ttl(&env);
 
-        // Emit admin added event
-        env.events().publish(
-            (symbol_short!("admin"),),
-            SecurityTokenEvent::AdminAdded(caller.clone(), new_admin),
+        // Emit events
+        Self::emit_burn(&env, &holder, balance);
+        Self::publish_event(
+            &env,
+            (symbol_short!("redeem"), holder.clone()),
+            LifecycleEvent::FinalRedemption(holder.clone(), balance, payout),
         );
+        if metadata.total_supply == 0 {
+            Self::publish_event(
+                &env,
+                (symbol_short!("retired"),),
+                LifecycleEvent::SupplyRetired(wind_down.paid),
+            );
+        }
 
-        Ok(())
+        Ok(payout)
     }
 
-    // Remove an admin from the token
-    pub fn remove_admin(env: Env, caller: Address, admin_to_remove: Address) -> Result<(), Error> {
+    // Split or reverse split the token. Holder balances are converted lazily with each
+    // split rounding down, and usdc_price also rounds down. Until every balance is converted,
+    // total_supply counts unconverted balances per epoch rounded down as a whole; the rest
+    // of the rounding dust is burned as each balance is converted.
+    pub fn split(
+        env: Env,
+        caller: Address,
+        numerator: u32,
+        denominator: u32,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
         caller.require_auth();
 
-        // Check if caller is issuer
-        if !Self::is_issuer(&env, &caller) {
-            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_SPLIT));
         }
 
-        // Check if trying to remove the issuer
-        if Self::is_issuer(&env, &admin_to_remove) {
-            return Err(Error::from_contract_error(ERR_CANNOT_REMOVE_ISSUER));
+        Self::require_active(&env)?;
+
+        // Validate ratio
+        let mut splits = Self::splits(env.clone());
+        if numerator == 0 || denominator == 0 || numerator == denominator || splits.len() >= MAX_SPLITS {
+            return Err(Error::from_contract_error(ERR_INVALID_SPLIT_RATIO));
         }
 
-        // Check if the address is actually an admin
-        if !Self::is_admin(&env, &admin_to_remove) {
-            return Err(Error::from_contract_error(ERR_NOT_AN_ADMIN));
+        // Debt notes carry a face value per token and are not re-denominated
+        if Self::debt_terms(env.clone()).is_some() {
+            return Err(Error::from_contract_error(ERR_INVALID_SPLIT_RATIO));
         }
 
-        // Get current admin list from INSTANCE storage
-        let admins: Vec<Address> = env
-            .storage()
-            .instance()
-            .get(&ADMINS_KEY)
-            .unwrap();
+        // Offering positions are recorded in pre-split units
+        if let Some(offering) = Self::offering(env.clone()) {
+            Self::require_offering_settled(&env, &offering)?;
+        }
 
-        // Remove the admin from the list
-        let mut new_admins = Vec::new(&env);
-        for admin in admins.iter() {
-            if &admin != &admin_to_remove {
-                new_admins.push_back(admin);
-            }
+        // Every pooled amount converts to at most the supply, so the supply bounds overflow
+        let mut metadata = Self::get_metadata(&env);
+        if metadata.total_supply.checked_mul(numerator as i128).is_none() {
+            return Err(Error::from_contract_error(ERR_CALCULATION_OVERFLOW));
         }
 
-        // Update storage with new admin list
-        env.storage().instance().set(&ADMINS_KEY, &new_admins);
+        // Balances already in current units join the pool awaiting this split
+        let epoch = splits.len();
+        let mut pool = Self::split_pool(&env);
+        let mut current = metadata.total_supply;
+        for (pooled_epoch, pooled) in pool.iter() {
+            current -= Self::convert_from_epoch(&splits, pooled_epoch, pooled);
+        }
+        if current > 0 {
+            pool.set(epoch, current);
+        }
+        splits.push_back(StockSplit {
+            numerator,
+            denominator,
+            timestamp: env.ledger().timestamp(),
+        });
+        let mut total_supply = 0;
+        for (pooled_epoch, pooled) in pool.iter() {
+            total_supply += Self::convert_from_epoch(&splits, pooled_epoch, pooled);
+        }
 
-        // Emit admin removed event
-        env.events().publish(
-            (symbol_short!("adminrem"),),
-            SecurityTokenEvent::AdminRemoved(caller.clone(), admin_to_remove),
+        let usdc_price = metadata.usdc_price
+            .checked_mul(denominator as i128)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+            / numerator as i128;
+        if total_supply <= 0 || usdc_price <= 0 || usdc_price > MAX_USDC_PRICE {
+            return Err(Error::from_contract_error(ERR_INVALID_SPLIT_RATIO));
+        }
+
+        let previous_price = metadata.usdc_price;
+        metadata.total_supply = total_supply;
+        metadata.usdc_price = usdc_price;
+        env.storage().instance().set(&METADATA_KEY, &metadata);
+        env.storage().instance().set(&SPLITS_KEY, &splits);
+        env.storage().instance().set(&SPLIT_POOL_KEY, &pool);
+
+        // Append to price history
+        Self::record_price_change(&env, &caller, previous_price, usdc_price);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("split"), caller.clone()),
+            LifecycleEvent::Split(numerator, denominator, total_supply, usdc_price),
         );
 
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("split"), &caller, None, justification);
+
         Ok(())
     }
 
-    // Configure authorization flags
-    pub fn configure_authorization(
+    // Apply pending splits to a holder's stored balance, burning its rounding dust. Anyone can
+    // call this, e.g. for holders whose balance rounded to zero and will not transact again.
+    pub fn convert_balance(env: Env, holder: Address) -> Result<i128, Error> {
+        let balance = Self::balance(env.clone(), holder.clone());
+        Self::write_balance(&env, &holder, balance)?;
+
+        Ok(balance)
+    }
+
+    // Issuer-only function to register a successor contract and stop transfers and purchases.
+    // Holders move their balances with migrate_to_successor once the timelock has passed.
+    pub fn start_migration(
         env: Env,
         caller: Address,
-        required: bool,
-        revocable: bool,
+        successor: Address,
+        numerator: u32,
+        denominator: u32,
+        justification: Option<Justification>,
     ) -> Result<(), Error> {
         caller.require_auth();
 
-        // Check if caller is admin
-        if !Self::is_admin(&env, &caller) {
-            return Err(Error::from_contract_error(ERR_NOT_ADMIN_CONFIGURE_AUTH));
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
         }
 
-        // Update configuration in INSTANCE storage
-        let mut config = Self::get_config(&env);
-        config.authorization_required = required;
-        config.authorization_revocable = revocable;
-        env.storage().instance().set(&CONFIG_KEY, &config);
+        Self::require_active(&env)?;
+
+        // Validate successor and exchange ratio
+        if successor == env.current_contract_address() || numerator == 0 || denominator == 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_MIGRATION));
+        }
+
+        // Offering escrow must be settled before balances move
+        if let Some(offering) = Self::offering(env.clone()) {
+            Self::require_offering_settled(&env, &offering)?;
+        }
+
+        let migration = Migration {
+            successor: successor.clone(),
+            numerator,
+            denominator,
+            supply: Self::get_metadata(&env).total_supply,
+            migrated: 0,
+            issued: 0,
+            started_at: env.ledger().timestamp(),
+            executable_at: env.ledger().sequence().saturating_add(MIGRATION_TIMELOCK_LEDGERS),
+        };
+        env.storage().instance().set(&MIGRATION_KEY, &migration);
 
         // Extend instance TTL
         Self::extend_instance_ttl(&env);
 
         // Emit event
-        env.events().publish(
-            (symbol_short!("auth"),),
-            SecurityTokenEvent::AuthorizationChanged(required, revocable),
+        Self::publish_event(
+            &env,
+            (symbol_short!("migrate"), caller.clone()),
+            LifecycleEvent::MigrationStarted(successor, numerator, denominator, migration.supply),
         );
 
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("migrate"), &caller, None, justification);
+
         Ok(())
     }
 
-    // Direct purchase tokens with USDC
-    pub fn purchase(
+    // Cancel a migration before any holder has moved (guardian or issuer), which
+    // resumes transfers and purchases
+    pub fn cancel_migration(
         env: Env,
-        buyer: Address,
-        beneficiary: Address,
-        token_amount: i128,
+        caller: Address,
+        justification: Option<Justification>,
     ) -> Result<(), Error> {
-        buyer.require_auth();
+        caller.require_auth();
 
-        // Validate amount
-        if token_amount <= 0 {
-            return Err(Error::from_contract_error(ERR_INVALID_PURCHASE_AMOUNT));
+        if !Self::is_guardian(&env, &caller) && !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_GUARDIAN));
         }
 
-        // Load metadata from instance storage
-        let metadata = Self::get_metadata(&env);
-
-        // Check KYC and compliance status for buyer and beneficiary
-        let config = Self::get_config(&env);
-        Self::check_compliance_requirements(&env, &config, &metadata.issuer, &buyer)?;
-        Self::check_compliance_requirements(&env, &config, &metadata.issuer, &beneficiary)?;
-
-        // Calculate USDC amount needed
-        let decimals_pow = DECIMAL_BASE.checked_pow(metadata.decimals)
-            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
-
-        let usdc_amount = token_amount.checked_mul(metadata.usdc_price)
-            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
-            .checked_div(decimals_pow)
-            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
-
-        if usdc_amount <= 0 {
-            return Err(Error::from_contract_error(ERR_CALCULATION_OVERFLOW));
+        let migration = Self::migration(env.clone())
+            .ok_or(Error::from_contract_error(ERR_NOT_MIGRATING))?;
+        if migration.migrated > 0 {
+            return Err(Error::from_contract_error(ERR_MIGRATION_UNDERWAY));
         }
+        env.storage().instance().remove(&MIGRATION_KEY);
 
-        // Get USDC token client
-        let usdc_token_client = token::Client::new(&env, &metadata.usdc_token);
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
 
-        // Verify buyer has sufficient USDC balance BEFORE transfer
-        let buyer_usdc_balance_before = usdc_token_client.balance(&buyer);
-        if buyer_usdc_balance_before < usdc_amount {
-            return Err(Error::from_contract_error(ERR_INSUFFICIENT_USDC_BALANCE));
-        }
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("migcancel"), caller.clone()),
+            LifecycleEvent::MigrationCancelled(caller.clone(), migration.successor),
+        );
 
-        // Get contract's initial USDC balance for verification
-        let contract_usdc_balance_before = usdc_token_client.balance(&env.current_contract_address());
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("migcancel"), &caller, None, justification);
 
-        // Transfer USDC from buyer to contract
-        usdc_token_client.transfer(&buyer, &env.current_contract_address(), &usdc_amount);
+        Ok(())
+    }
 
-        // Verify the transfer actually occurred by checking balances
-        let buyer_usdc_balance_after = usdc_token_client.balance(&buyer);
-        let contract_usdc_balance_after = usdc_token_client.balance(&env.current_contract_address());
+    // Burn holder balances here and mint them on the successor with KYC and compliance
+    // state carried over. Holders migrate themselves; admins can migrate anyone in batches.
+    pub fn migrate_to_successor(
+        env: Env,
+        caller: Address,
+        holders: Vec<Address>,
+        justification: Option<Justification>,
+    ) -> Result<i128, Error> {
+        caller.require_auth();
 
-        // Verify buyer's balance decreased by the expected amount
-        let expected_buyer_balance = buyer_usdc_balance_before.checked_sub(usdc_amount)
-            .ok_or(Error::from_contract_error(ERR_USDC_TRANSFER_VERIFICATION_FAILED))?;
-        
-        if buyer_usdc_balance_after != expected_buyer_balance {
-            return Err(Error::from_contract_error(ERR_USDC_TRANSFER_VERIFICATION_FAILED));
+        let mut migration = Self::migration(env.clone())
+            .ok_or(Error::from_contract_error(ERR_NOT_MIGRATING))?;
+        if env.ledger().sequence() < migration.executable_at {
+            return Err(Error::from_contract_error(ERR_MIGRATION_TIMELOCKED));
         }
 
-        // Verify contract's balance increased by the expected amount
-        let expected_contract_balance = contract_usdc_balance_before.checked_add(usdc_amount)
-            .ok_or(Error::from_contract_error(ERR_USDC_TRANSFER_VERIFICATION_FAILED))?;
-        
-        if contract_usdc_balance_after != expected_contract_balance {
-            return Err(Error::from_contract_error(ERR_USDC_TRANSFER_VERIFICATION_FAILED));
+        // Validate batch size
+        if holders.is_empty() || holders.len() > MAX_MIGRATION_BATCH_SIZE {
+            return Err(Error::from_contract_error(ERR_INVALID_BATCH));
         }
 
-        // Get balances using helper functions
-        let issuer_balance_key = DataKey::Balance(metadata.issuer.clone());
-        let beneficiary_balance_key = DataKey::Balance(beneficiary.clone());
+        let is_admin = Self::is_admin(&env, &caller);
+        let successor = SuccessorTokenClient::new(&env, &migration.successor);
+        let mut total_burned = 0;
+        let mut total_issued = 0;
 
-        let issuer_balance = Self::balance(env.clone(), metadata.issuer.clone());
-        let beneficiary_balance = Self::balance(env.clone(), beneficiary.clone());
+        for holder in holders.iter() {
+            if holder != caller && !is_admin {
+                return Err(Error::from_contract_error(ERR_NOT_ADMIN_MIGRATION));
+            }
 
-        // Check if issuer has enough tokens
-        if issuer_balance < token_amount {
-            return Err(Error::from_contract_error(ERR_INSUFFICIENT_ISSUER_TOKENS));
+            let balance = Self::balance(env.clone(), holder.clone());
+            if balance == 0 {
+                continue;
+            }
+            let issued = balance
+                .checked_mul(migration.numerator as i128)
+                .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+                / migration.denominator as i128;
+
+            // Burn the old balance, including any units held in named partitions
+            Self::drain_partitions(&env, &holder, balance);
+            Self::write_balance(&env, &holder, 0)?;
+            migration.migrated += balance;
+            migration.issued += issued;
+            total_burned += balance;
+            total_issued += issued;
+
+            // Mint on the successor with identity state carried over
+            successor.migrate_mint(
+                &holder,
+                &issued,
+                &Self::is_kyc_verified(env.clone(), holder.clone()),
+                &Self::check_compliance(env.clone(), holder.clone()),
+            );
+
+            // Emit events
+            Self::emit_burn(&env, &holder, balance);
+            Self::publish_event(
+                &env,
+                (symbol_short!("migrated"), holder.clone()),
+                LifecycleEvent::Migrated(holder.clone(), balance, issued),
+            );
+
+            // Record audit log entry when an admin migrates another holder
+            if holder != caller {
+                Self::record_audit(
+                    &env,
+                    symbol_short!("migrated"),
+                    &caller,
+                    Some(holder),
+                    justification.clone(),
+                );
+            }
         }
 
-        // Update token balances in PERSISTENT storage
-        let new_issuer_balance = issuer_balance.checked_sub(token_amount)
-            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
-        let new_beneficiary_balance = beneficiary_balance.checked_add(token_amount)
-            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
-
-        env.storage()
-            .persistent()
-            .set(&issuer_balance_key, &new_issuer_balance);
-        env.storage()
-            .persistent()
-            .set(&beneficiary_balance_key, &new_beneficiary_balance);
-
-        // Extend TTLs for issuer and beneficiary balances
-        Self::extend_persistent_ttl(&env, &issuer_balance_key);
-        Self::extend_persistent_ttl(&env, &beneficiary_balance_key);
+        if total_burned == 0 {
+            return Err(Error::from_contract_error(ERR_NOTHING_TO_MIGRATE));
+        }
 
-        // Update USDC balance using helper
-        let current_usdc_balance = Self::usdc_balance(env.clone());
-        let new_usdc_balance = current_usdc_balance.checked_add(usdc_amount)
-            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
-        env.storage().instance().set(&USDC_BAL_KEY, &new_usdc_balance);
+        // Converting balances may have burned split rounding dust, so reload the supply
+        let mut metadata = Self::get_metadata(&env);
+        metadata.total_supply -= total_burned;
+        env.storage().instance().set(&METADATA_KEY, &metadata);
+        env.storage().instance().set(&MIGRATION_KEY, &migration);
 
-        // Emit purchase event
-        env.events().publish(
-            (symbol_short!("purchase"),),
-            SecurityTokenEvent::Purchase(buyer.clone(), beneficiary.clone(), token_amount, usdc_amount),
-        );
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
 
-        Ok(())
+        Ok(total_issued)
     }
 
-    // Issuer-only function to withdraw accumulated USDC
-    pub fn withdraw_usdc(
+    // Enable debt mode with coupon and maturity terms (issuer only, once)
+    pub fn set_debt_terms(
         env: Env,
         caller: Address,
-        amount: i128,
+        terms: DebtTerms,
+        justification: Option<Justification>,
     ) -> Result<(), Error> {
         caller.require_auth();
 
-        // Check if caller is issuer (only issuer can withdraw USDC)
+        // Check if caller is issuer
         if !Self::is_issuer(&env, &caller) {
             return Err(Error::from_contract_error(ERR_NOT_ISSUER));
         }
 
-        // Get USDC balance using helper
-        let usdc_balance = Self::usdc_balance(env.clone());
-
-        // Validate amount
-        if amount <= 0 || amount > usdc_balance {
-            return Err(Error::from_contract_error(ERR_INVALID_WITHDRAW_AMOUNT));
-        }
-
-        // Get metadata for USDC token address
-        let metadata = Self::get_metadata(&env);
-        let usdc_token_client = token::Client::new(&env, &metadata.usdc_token);
-
-        // Get initial balances for verification
-        let contract_usdc_balance_before = usdc_token_client.balance(&env.current_contract_address());
-        let admin_usdc_balance_before = usdc_token_client.balance(&caller);
-
-        // Verify contract has sufficient USDC before withdrawal
-        if contract_usdc_balance_before < amount {
-            return Err(Error::from_contract_error(ERR_INSUFFICIENT_USDC_IN_CONTRACT));
-        }
-
-        // Transfer USDC from contract to admin
-        usdc_token_client.transfer(&env.current_contract_address(), &caller, &amount);
-
-        // Verify the transfer actually occurred by checking balances
-        let contract_usdc_balance_after = usdc_token_client.balance(&env.current_contract_address());
-        let admin_usdc_balance_after = usdc_token_client.balance(&caller);
-
-        // Verify contract's balance decreased by the expected amount
-        let expected_contract_balance = contract_usdc_balance_before.checked_sub(amount)
-            .ok_or(Error::from_contract_error(ERR_USDC_WITHDRAWAL_VERIFICATION_FAILED))?;
-        
-        if contract_usdc_balance_after != expected_contract_balance {
-            return Err(Error::from_contract_error(ERR_USDC_WITHDRAWAL_VERIFICATION_FAILED));
+        // Terms are fixed once set
+        if Self::debt_terms(env.clone()).is_some() {
+            return Err(Error::from_contract_error(ERR_INVALID_DEBT_TERMS));
         }
 
-        // Verify admin's balance increased by the expected amount
-        let expected_admin_balance = admin_usdc_balance_before.checked_add(amount)
-            .ok_or(Error::from_contract_error(ERR_USDC_WITHDRAWAL_VERIFICATION_FAILED))?;
-        
-        if admin_usdc_balance_after != expected_admin_balance {
-            return Err(Error::from_contract_error(ERR_USDC_WITHDRAWAL_VERIFICATION_FAILED));
+        // Validate terms
+        if terms.face_value <= 0
+            || terms.coupon_bps as i128 > BPS_DENOMINATOR
+            || terms.period == 0
+            || terms.first_payment < env.ledger().timestamp()
+            || terms.maturity < terms.first_payment
+        {
+            return Err(Error::from_contract_error(ERR_INVALID_DEBT_TERMS));
         }
 
-        // Update USDC balance in INSTANCE storage
-        let new_usdc_balance = usdc_balance.checked_sub(amount)
-            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
-        env.storage().instance().set(&USDC_BAL_KEY, &new_usdc_balance);
+        env.storage().instance().set(&DEBT_KEY, &terms);
+        env.storage().instance().set(
+            &COUPON_KEY,
+            &CouponState {
+                index: 0,
+                payments: 0,
+                due: 0,
+                funded: 0,
+                claimed: 0,
+            },
+        );
 
         // Extend instance TTL
         Self::extend_instance_ttl(&env);
 
-        // Emit withdrawal event
-        env.events().publish(
-            (symbol_short!("withdraw"),),
-            SecurityTokenEvent::UsdcWithdrawn(caller.clone(), amount),
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("debt"), caller.clone()),
+            LifecycleEvent::DebtTermsSet(terms),
         );
 
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("debt"), &caller, None, justification);
+
         Ok(())
     }
 
-    // Set transfer restriction flag
-    pub fn set_transfer_restriction(
+    // Deposit the coupons owed for every payment date that has passed. Each date accrues to
+    // the holders on that date, so funding late does not move coupons to later buyers.
+    pub fn fund_coupon(
         env: Env,
         caller: Address,
-        restricted: bool,
-    ) -> Result<(), Error> {
+        justification: Option<Justification>,
+    ) -> Result<i128, Error> {
         caller.require_auth();
 
         // Check if caller is admin
         if !Self::is_admin(&env, &caller) {
-            return Err(Error::from_contract_error(ERR_NOT_ADMIN_TRANSFER_RESTRICTION));
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_DEBT));
         }
 
-        // Update configuration in INSTANCE storage
-        let mut config = Self::get_config(&env);
-        config.transfer_restricted = restricted;
-        env.storage().instance().set(&CONFIG_KEY, &config);
+        let mut coupon = Self::roll_coupon(&env)?
+            .ok_or(Error::from_contract_error(ERR_NOT_DEBT_TOKEN))?;
+
+        // A payment date must have passed since the last funding
+        let amount = coupon.due - coupon.funded;
+        if amount <= 0 {
+            return Err(Error::from_contract_error(ERR_COUPON_NOT_DUE));
+        }
+
+        // Transfer coupon from caller to contract with balance verification
+        let metadata = Self::get_metadata(&env);
+        Self::transfer_in(&env, &metadata.usdc_token, &caller, amount)?;
+
+        coupon.funded += amount;
+        env.storage().instance().set(&COUPON_KEY, &coupon);
 
         // Extend instance TTL
         Self::extend_instance_ttl(&env);
 
-        // Emit transfer restriction changed event
-        env.events().publish(
-            (symbol_short!("restrict"),),
-            SecurityTokenEvent::TransferRestrictionChanged(restricted),
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("coupon"), caller.clone()),
+            LifecycleEvent::CouponFunded(coupon.payments, amount),
         );
 
-        Ok(())
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("coupon"), &caller, None, justification);
+
+        Ok(amount)
     }
 
-    // Admin function to extend instance storage TTL on-demand
-    pub fn bump_instance_ttl(env: Env, caller: Address) -> Result<(), Error> {
-        caller.require_auth();
+    // Claim all coupons accrued to a holder
+    pub fn claim_coupon(env: Env, holder: Address) -> Result<i128, Error> {
+        holder.require_auth();
 
-        // Check if caller is admin
-        if !Self::is_admin(&env, &caller) {
-            return Err(Error::from_contract_error(ERR_NOT_ADMIN_TTL));
+        let mut coupon = Self::roll_coupon(&env)?
+            .ok_or(Error::from_contract_error(ERR_NOT_DEBT_TOKEN))?;
+
+        // Claims wait until every passed payment date is funded
+        if coupon.funded < coupon.due {
+            return Err(Error::from_contract_error(ERR_COUPON_UNFUNDED));
         }
 
+        // Settle accrual since the last balance change
+        Self::settle_coupon(&env, &holder)?;
+
+        let account_key = DataKey::CouponAccount(holder.clone());
+        let mut account = Self::coupon_account(env.clone(), holder.clone());
+        if account.accrued <= 0 {
+            return Err(Error::from_contract_error(ERR_NOTHING_TO_CLAIM));
+        }
+        let amount = account.accrued;
+
+        // Transfer from contract to holder with balance verification
+        let metadata = Self::get_metadata(&env);
+        Self::transfer_out(&env, &metadata.usdc_token, &holder, amount)?;
+
+        account.accrued = 0;
+        env.storage().persistent().set(&account_key, &account);
+        Self::extend_persistent_ttl(&env, &account_key);
+
+        coupon.claimed += amount;
+        env.storage().instance().set(&COUPON_KEY, &coupon);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("couponclm"), holder.clone()),
+            LifecycleEvent::CouponClaimed(holder, amount),
+        );
+
+        Ok(amount)
+    }
+
+    // Deposit the principal at maturity; holders then redeem through redeem_final
+    pub fn mature(
+        env: Env,
+        caller: Address,
+        justification: Option<Justification>,
+    ) -> Result<i128, Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_DEBT));
+        }
+
+        let terms = Self::debt_terms(env.clone())
+            .ok_or(Error::from_contract_error(ERR_NOT_DEBT_TOKEN))?;
+        let coupon = Self::roll_coupon(&env)?
+            .ok_or(Error::from_contract_error(ERR_NOT_DEBT_TOKEN))?;
+
+        // Maturity must have passed and every coupon date must be funded
+        if env.ledger().timestamp() < terms.maturity || coupon.funded < coupon.due {
+            return Err(Error::from_contract_error(ERR_NOT_MATURED));
+        }
+
+        let metadata = Self::get_metadata(&env);
+        let principal = metadata.total_supply
+            .checked_mul(terms.face_value)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+            / DECIMAL_BASE.pow(metadata.decimals);
+
+        Self::begin_wind_down(&env, &caller, principal)?;
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("mature"), &caller, None, justification);
+
+        Ok(principal)
+    }
+
+    // Set KYC verification status for an address
+    pub fn set_kyc_status(
+        env: Env,
+        caller: Address,
+        address: Address,
+        verified: bool,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_KYC));
+        }
+
+        let config = Self::get_config(&env);
+        Self::write_kyc_status(&env, &config, &address, verified)?;
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("kyc"), &caller, Some(address), justification);
+
+        Ok(())
+    }
+
+    // Set compliance status for an address
+    pub fn set_compliance_status(
+        env: Env,
+        caller: Address,
+        address: Address,
+        status: ComplianceStatus,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_COMPLIANCE));
+        }
+
+        let config = Self::get_config(&env);
+        Self::write_compliance_status(&env, &config, &address, status)?;
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("complianc"), &caller, Some(address), justification);
+
+        Ok(())
+    }
+
+    // Apply KYC, compliance and jurisdiction records for a cohort of investors.
+    // All records are applied or none are.
+    pub fn onboard_investors(
+        env: Env,
+        caller: Address,
+        records: Vec<OnboardingRecord>,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_KYC));
+        }
+
+        // Validate batch size
+        if records.is_empty() || records.len() > MAX_ONBOARDING_BATCH_SIZE {
+            return Err(Error::from_contract_error(ERR_INVALID_BATCH));
+        }
+
+        let config = Self::get_config(&env);
+        for record in records.iter() {
+            Self::write_kyc_status(&env, &config, &record.address, record.kyc_verified)?;
+            Self::write_compliance_status(&env, &config, &record.address, record.compliance)?;
+
+            // Update jurisdiction in PERSISTENT storage
+            let jurisdiction_key = DataKey::Jurisdiction(record.address.clone());
+            env.storage()
+                .persistent()
+                .set(&jurisdiction_key, &record.jurisdiction);
+            Self::extend_persistent_ttl(&env, &jurisdiction_key);
+
+            // Emit event
+            Self::publish_event(
+                &env,
+                (symbol_short!("juris"), record.address.clone()),
+                SecurityTokenEvent::JurisdictionUpdated(record.address.clone(), record.jurisdiction),
+            );
+
+            // Record audit log entry
+            Self::record_audit(
+                &env,
+                symbol_short!("onboard"),
+                &caller,
+                Some(record.address),
+                justification.clone(),
+            );
+        }
+
+        Ok(())
+    }
+
+    // Execute clawback of tokens (regulatory action)
+    pub fn clawback(
+        env: Env,
+        caller: Address,
+        from: Address,
+        amount: i128,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_CLAWBACK));
+        }
+
+        // Validate amount is positive
+        if amount <= 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_AMOUNT));
+        }
+
+        // Get current balance using helper
+        let current_balance = Self::balance(env.clone(), from.clone());
+
+        // Clawback the minimum of requested amount and available balance
+        // This ensures we take what's available rather than failing if exact amount isn't present
+        let actual_clawback_amount = if current_balance < amount {
+            current_balance
+        } else {
+            amount
+        };
+
+        // Take from the default partition first, then from named partitions
+        let from_default = Self::default_balance(&env, &from).min(actual_clawback_amount);
+        Self::drain_partitions(&env, &from, actual_clawback_amount - from_default);
+
+        // Get issuer address from metadata
+        let metadata = Self::get_metadata(&env);
+
+        // Get issuer's current balance from PERSISTENT storage
+        let issuer_balance = Self::balance(env.clone(), metadata.issuer.clone());
+
+        // Update balances in PERSISTENT storage
+        let new_balance = current_balance.checked_sub(actual_clawback_amount)
+            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+
+        let new_issuer_balance = issuer_balance.checked_add(actual_clawback_amount)
+            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+
+        Self::write_balance(&env, &from, new_balance)?;
+        Self::write_balance(&env, &metadata.issuer, new_issuer_balance)?;
+
+        // Clawed back tokens move to the issuer without changing supply, so the SEP-41
+        // event is a transfer; the clawing admin is recorded in the audit log
+        Self::emit_transfer(&env, &from, &metadata.issuer, actual_clawback_amount);
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("clawback"), &caller, Some(from), justification);
+
+        Ok(())
+    }
+
+    // Add an admin to the token (issuer only)
+    pub fn add_admin(
+        env: Env,
+        caller: Address,
+        new_admin: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer (only issuer can add admins)
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_ADD_ADMIN));
+        }
+
+        // Check if already an admin using helper
+        if Self::is_admin(&env, &new_admin) {
+            return Err(Error::from_contract_error(ERR_DUPLICATE_ADMIN));
+        }
+
+        // Get current admin list from INSTANCE storage
+        let mut admins: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&ADMINS_KEY)
+            .unwrap();
+
+        // Add to admin list
+        admins.push_back(new_admin.clone());
+        env.storage().instance().set(&ADMINS_KEY, &admins);
+
+        // Extend instance TTL
         Self::extend_instance_ttl(&env);
 
+        // Emit admin added event
+        Self::publish_event(
+            &env,
+            (symbol_short!("admin"), caller.clone(), new_admin.clone()),
+            SecurityTokenEvent::AdminAdded(caller.clone(), new_admin.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("admin"), &caller, Some(new_admin), justification);
+
         Ok(())
     }
 
-    // Admin function to extend TTLs for multiple user addresses (bulk operation)
-    pub fn bump_user_ttls(env: Env, caller: Address, addresses: Vec<Address>) -> Result<(), Error> {
+    // Remove an admin from the token
+    pub fn remove_admin(
+        env: Env,
+        caller: Address,
+        admin_to_remove: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        // Check if trying to remove the issuer
+        if Self::is_issuer(&env, &admin_to_remove) {
+            return Err(Error::from_contract_error(ERR_CANNOT_REMOVE_ISSUER));
+        }
+
+        // Check if the address is actually an admin
+        if !Self::is_admin(&env, &admin_to_remove) {
+            return Err(Error::from_contract_error(ERR_NOT_AN_ADMIN));
+        }
+
+        // Get current admin list from INSTANCE storage
+        let admins: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&ADMINS_KEY)
+            .unwrap();
+
+        // Remove the admin from the list
+        let mut new_admins = Vec::new(&env);
+        for admin in admins.iter() {
+            if &admin != &admin_to_remove {
+                new_admins.push_back(admin);
+            }
+        }
+
+        // Update storage with new admin list
+        env.storage().instance().set(&ADMINS_KEY, &new_admins);
+
+        // Emit admin removed event
+        Self::publish_event(
+            &env,
+            (symbol_short!("adminrem"), caller.clone(), admin_to_remove.clone()),
+            SecurityTokenEvent::AdminRemoved(caller.clone(), admin_to_remove.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("adminrem"), &caller, Some(admin_to_remove), justification);
+
+        Ok(())
+    }
+
+    // Configure authorization flags
+    pub fn configure_authorization(
+        env: Env,
+        caller: Address,
+        required: bool,
+        revocable: bool,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
         caller.require_auth();
 
         // Check if caller is admin
         if !Self::is_admin(&env, &caller) {
-            return Err(Error::from_contract_error(ERR_NOT_ADMIN_TTL));
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_CONFIGURE_AUTH));
         }
 
-        for address in addresses.iter() {
-            // Extend balance TTL if exists
-            let balance_key = DataKey::Balance(address.clone());
-            Self::extend_persistent_ttl(&env, &balance_key);
+        // Update configuration in INSTANCE storage
+        let mut config = Self::get_config(&env);
+        config.authorization_required = required;
+        config.authorization_revocable = revocable;
+        env.storage().instance().set(&CONFIG_KEY, &config);
 
-            // Extend KYC TTL if exists
-            let kyc_key = DataKey::KycVerified(address.clone());
-            Self::extend_persistent_ttl(&env, &kyc_key);
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
 
-            // Extend compliance TTL if exists
-            let compliance_key = DataKey::ComplianceStatus(address.clone());
-            Self::extend_persistent_ttl(&env, &compliance_key);
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("auth"), caller.clone()),
+            SecurityTokenEvent::AuthorizationChanged(required, revocable),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("auth"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Direct purchase tokens with USDC
+    pub fn purchase(
+        env: Env,
+        buyer: Address,
+        beneficiary: Address,
+        token_amount: i128,
+        referrer: Option<Address>,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+
+        Self::purchase_usdc(&env, &buyer, &beneficiary, token_amount, None, &referrer)
+    }
+
+    // Purchase tokens with USDC, rejecting the purchase if the cost exceeds max_usdc
+    pub fn purchase_with_limit(
+        env: Env,
+        buyer: Address,
+        beneficiary: Address,
+        token_amount: i128,
+        max_usdc: i128,
+        referrer: Option<Address>,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+
+        Self::purchase_usdc(&env, &buyer, &beneficiary, token_amount, Some(max_usdc), &referrer)
+    }
+
+    // Purchase as many tokens as usdc_amount buys, rejecting if fewer than min_tokens.
+    // The token amount rounds down to the smallest token unit and only its cost (also
+    // rounded down, as in purchase) is transferred, so the buyer never pays more than
+    // usdc_amount and any remainder stays with the buyer.
+    pub fn purchase_for_usdc(
+        env: Env,
+        buyer: Address,
+        beneficiary: Address,
+        usdc_amount: i128,
+        min_tokens: i128,
+        referrer: Option<Address>,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+
+        // Validate amount
+        if usdc_amount <= 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_PURCHASE_AMOUNT));
+        }
+
+        // Calculate token amount for the USDC offered
+        let metadata = Self::get_metadata(&env);
+        let usdc_price = Self::resolve_price(&env, &metadata)?;
+        let decimals_pow = DECIMAL_BASE.checked_pow(metadata.decimals)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        let token_amount = usdc_amount.checked_mul(decimals_pow)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+            .checked_div(usdc_price)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+
+        if token_amount <= 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_PURCHASE_AMOUNT));
+        }
+        if token_amount < min_tokens {
+            return Err(Error::from_contract_error(ERR_SLIPPAGE_EXCEEDED));
+        }
+
+        Self::purchase_usdc(&env, &buyer, &beneficiary, token_amount, Some(usdc_amount), &referrer)
+    }
+
+    // Purchase tokens with an accepted non-USDC payment asset
+    pub fn purchase_with_asset(
+        env: Env,
+        buyer: Address,
+        beneficiary: Address,
+        token_amount: i128,
+        asset: Address,
+        referrer: Option<Address>,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+
+        // Load metadata and the asset's pricing from storage
+        let metadata = Self::get_metadata(&env);
+        let payment_asset = Self::payment_asset(env.clone(), asset.clone())
+            .ok_or(Error::from_contract_error(ERR_ASSET_NOT_ACCEPTED))?;
+
+        // Offering caps are denominated in USDC
+        if Self::offering(env.clone()).is_some() {
+            return Err(Error::from_contract_error(ERR_OFFERING_USDC_ONLY));
+        }
+
+        // Investment limits are denominated in USDC
+        let class = Self::investor_class(env.clone(), beneficiary.clone());
+        if Self::investment_limits(env.clone(), class).is_some() {
+            return Err(Error::from_contract_error(ERR_LIMITS_USDC_ONLY));
+        }
+
+        let asset_amount = Self::execute_purchase(
+            &env,
+            &metadata,
+            &buyer,
+            &beneficiary,
+            token_amount,
+            &asset,
+            payment_asset.price,
+            None,
+        )?;
+
+        // Split proceeds between the issuer, the platform fee and the referring broker
+        let fee = Self::calculate_fee(&env, asset_amount)?;
+        let referral = Self::calculate_commission(&env, &buyer, &referrer, token_amount, asset_amount)?;
+        Self::credit_proceeds(&env, &asset, asset_amount, fee, &referral, false)?;
+
+        // Emit purchase event
+        Self::publish_event(
+            &env,
+            (symbol_short!("purchasea"), buyer.clone(), beneficiary.clone()),
+            SecurityTokenEvent::AssetPurchase(
+                buyer.clone(),
+                beneficiary.clone(),
+                asset,
+                token_amount,
+                asset_amount,
+            ),
+        );
+
+        Ok(())
+    }
+
+    // Issuer-only function to withdraw accumulated USDC
+    pub fn withdraw_usdc(
+        env: Env,
+        caller: Address,
+        amount: i128,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        let metadata = Self::get_metadata(&env);
+        Self::withdraw_asset(env, caller, metadata.usdc_token, amount, justification)
+    }
+
+    // Issuer-only function to withdraw an accumulated payment asset
+    pub fn withdraw_asset(
+        env: Env,
+        caller: Address,
+        asset: Address,
+        amount: i128,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Transfer proceeds to the issuer
+        let mut payouts = Vec::new(&env);
+        payouts.push_back((caller.clone(), amount));
+        Self::withdraw_proceeds(&env, &caller, &asset, &payouts, false)?;
+
+        // Emit withdrawal event
+        let metadata = Self::get_metadata(&env);
+        if asset == metadata.usdc_token {
+            Self::publish_event(
+                &env,
+                (symbol_short!("withdraw"), caller.clone()),
+                SecurityTokenEvent::UsdcWithdrawn(caller.clone(), amount),
+            );
+            Self::record_audit(&env, symbol_short!("withdraw"), &caller, None, justification);
+        } else {
+            Self::publish_event(
+                &env,
+                (symbol_short!("withdrawa"), caller.clone(), asset.clone()),
+                SecurityTokenEvent::AssetWithdrawn(caller.clone(), asset, amount),
+            );
+            Self::record_audit(&env, symbol_short!("withdrawa"), &caller, None, justification);
+        }
+
+        Ok(())
+    }
+
+    // Issuer-only function to withdraw USDC to an allowlisted destination
+    pub fn withdraw_usdc_to(
+        env: Env,
+        caller: Address,
+        destination: Address,
+        amount: i128,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let metadata = Self::get_metadata(&env);
+        let mut payouts = Vec::new(&env);
+        payouts.push_back((destination.clone(), amount));
+        Self::withdraw_proceeds(&env, &caller, &metadata.usdc_token, &payouts, false)?;
+
+        // Emit withdrawal event
+        Self::emit_withdrawals(&env, &caller, &payouts);
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("withdrawt"), &caller, Some(destination), justification);
+
+        Ok(())
+    }
+
+    // Issuer-only function to split a USDC withdrawal across allowlisted destinations by
+    // basis points. Shares round down and the last recipient receives the remainder.
+    pub fn withdraw_usdc_split(
+        env: Env,
+        caller: Address,
+        amount: i128,
+        splits: Vec<(Address, u32)>,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Validate split
+        if splits.is_empty() || splits.len() > MAX_SPLIT_RECIPIENTS {
+            return Err(Error::from_contract_error(ERR_INVALID_SPLIT));
+        }
+        let mut total_bps: i128 = 0;
+        for (_, bps) in splits.iter() {
+            total_bps += bps as i128;
+        }
+        if total_bps != BPS_DENOMINATOR {
+            return Err(Error::from_contract_error(ERR_INVALID_SPLIT));
+        }
+
+        // Calculate each recipient's share
+        let mut payouts = Vec::new(&env);
+        let mut remaining = amount;
+        for (index, (destination, bps)) in splits.iter().enumerate() {
+            let share = if index as u32 == splits.len() - 1 {
+                remaining
+            } else {
+                amount.checked_mul(bps as i128)
+                    .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+                    / BPS_DENOMINATOR
+            };
+            remaining -= share;
+            if share > 0 {
+                payouts.push_back((destination, share));
+            }
+        }
+
+        let metadata = Self::get_metadata(&env);
+        Self::withdraw_proceeds(&env, &caller, &metadata.usdc_token, &payouts, false)?;
+
+        // Emit withdrawal events
+        Self::emit_withdrawals(&env, &caller, &payouts);
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("wdsplit"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Set the guardian. The issuer appoints the first guardian; after that only the
+    // current guardian can hand the role over, so a compromised issuer key cannot.
+    pub fn set_guardian(
+        env: Env,
+        caller: Address,
+        guardian: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let authorized = match Self::guardian(env.clone()) {
+            Some(current) => caller == current,
+            None => Self::is_issuer(&env, &caller),
+        };
+        if !authorized {
+            return Err(Error::from_contract_error(ERR_NOT_GUARDIAN));
+        }
+
+        env.storage().instance().set(&GUARDIAN_KEY, &guardian);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("guardian"), caller.clone(), guardian.clone()),
+            SecurityTokenEvent::GuardianUpdated(caller.clone(), guardian.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("guardian"), &caller, Some(guardian), justification);
+
+        Ok(())
+    }
+
+    // Configure USDC withdrawal limits (guardian once appointed; before that the issuer,
+    // who can only tighten an existing policy)
+    pub fn set_withdrawal_policy(
+        env: Env,
+        caller: Address,
+        policy: WithdrawalPolicy,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let authorized = match Self::guardian(env.clone()) {
+            Some(guardian) => caller == guardian,
+            None => Self::is_issuer(&env, &caller),
+        };
+        if !authorized {
+            return Err(Error::from_contract_error(ERR_NOT_GUARDIAN));
+        }
+
+        // Without a guardian the issuer can only tighten an existing policy
+        if Self::guardian(env.clone()).is_none() {
+            if let Some(current) = Self::withdrawal_policy(env.clone()) {
+                if Self::loosens_policy(&current, &policy) {
+                    return Err(Error::from_contract_error(ERR_POLICY_REQUIRES_GUARDIAN));
+                }
+            }
+        }
+
+        // Validate policy
+        if policy.period_cap < 0 || policy.timelock_threshold < 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_WITHDRAWAL_POLICY));
+        }
+        if policy.period_cap > 0 && policy.period_ledgers == 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_WITHDRAWAL_POLICY));
+        }
+        if policy.timelock_threshold > 0 && policy.timelock_ledgers == 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_WITHDRAWAL_POLICY));
+        }
+
+        env.storage().instance().set(&WD_POLICY_KEY, &policy);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("wdpolicy"), caller.clone()),
+            SecurityTokenEvent::WithdrawalPolicyUpdated(caller.clone(), policy),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("wdpolicy"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Issuer-only function to queue a USDC withdrawal for execution after the timelock
+    pub fn queue_withdrawal(
+        env: Env,
+        caller: Address,
+        destination: Address,
+        amount: i128,
+        justification: Option<Justification>,
+    ) -> Result<u32, Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        // Validate destination and amount
+        if !Self::is_withdrawal_destination(env.clone(), destination.clone()) {
+            return Err(Error::from_contract_error(ERR_DESTINATION_NOT_ALLOWED));
+        }
+        if amount <= 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_WITHDRAW_AMOUNT));
+        }
+
+        let timelock_ledgers = Self::withdrawal_policy(env.clone())
+            .map(|policy| policy.timelock_ledgers)
+            .unwrap_or(0);
+        let executable_at = env.ledger().sequence().saturating_add(timelock_ledgers);
+
+        // Store the queued withdrawal in PERSISTENT storage
+        let id: u32 = env.storage().instance().get(&WD_QUEUE_KEY).unwrap_or(0);
+        let withdrawal_key = DataKey::QueuedWithdrawal(id);
+        env.storage().persistent().set(
+            &withdrawal_key,
+            &QueuedWithdrawal {
+                destination: destination.clone(),
+                amount,
+                executable_at,
+            },
+        );
+        env.storage().instance().set(&WD_QUEUE_KEY, &(id + 1));
+
+        // Extend TTLs
+        Self::extend_persistent_ttl(&env, &withdrawal_key);
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("wdqueue"), destination.clone()),
+            SecurityTokenEvent::WithdrawalQueued(id, destination.clone(), amount, executable_at),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("wdqueue"), &caller, Some(destination), justification);
+
+        Ok(id)
+    }
+
+    // Issuer-only function to execute a queued withdrawal once its timelock has passed
+    pub fn execute_withdrawal(
+        env: Env,
+        caller: Address,
+        id: u32,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let withdrawal_key = DataKey::QueuedWithdrawal(id);
+        let withdrawal = Self::queued_withdrawal(env.clone(), id)
+            .ok_or(Error::from_contract_error(ERR_WITHDRAWAL_NOT_FOUND))?;
+
+        if env.ledger().sequence() < withdrawal.executable_at {
+            return Err(Error::from_contract_error(ERR_WITHDRAWAL_TIMELOCKED));
+        }
+
+        let metadata = Self::get_metadata(&env);
+        let mut payouts = Vec::new(&env);
+        payouts.push_back((withdrawal.destination.clone(), withdrawal.amount));
+        Self::withdraw_proceeds(&env, &caller, &metadata.usdc_token, &payouts, true)?;
+
+        env.storage().persistent().remove(&withdrawal_key);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("wdexec"), withdrawal.destination.clone()),
+            SecurityTokenEvent::WithdrawalExecuted(id, withdrawal.destination.clone(), withdrawal.amount),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("wdexec"), &caller, Some(withdrawal.destination), justification);
+
+        Ok(())
+    }
+
+    // Cancel a queued withdrawal (guardian or issuer)
+    pub fn cancel_withdrawal(
+        env: Env,
+        caller: Address,
+        id: u32,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        if !Self::is_guardian(&env, &caller) && !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_GUARDIAN));
+        }
+
+        let withdrawal_key = DataKey::QueuedWithdrawal(id);
+        if !env.storage().persistent().has(&withdrawal_key) {
+            return Err(Error::from_contract_error(ERR_WITHDRAWAL_NOT_FOUND));
+        }
+        env.storage().persistent().remove(&withdrawal_key);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("wdcancel"), caller.clone()),
+            SecurityTokenEvent::WithdrawalCancelled(id, caller.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("wdcancel"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Admin function to credit untracked USDC sent directly to the contract to the issuer
+    pub fn reconcile_usdc(
+        env: Env,
+        caller: Address,
+        justification: Option<Justification>,
+    ) -> Result<i128, Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_TREASURY));
+        }
+
+        let reconciliation = Self::usdc_reconciliation(env.clone());
+        if reconciliation.surplus <= 0 {
+            return Err(Error::from_contract_error(ERR_NOTHING_TO_RECONCILE));
+        }
+
+        // Record the surplus as issuer proceeds so it is withdrawable under the usual rules
+        let metadata = Self::get_metadata(&env);
+        let new_usdc_balance = Self::usdc_balance(env.clone())
+            .checked_add(reconciliation.surplus)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        Self::set_asset_balance(&env, &metadata.usdc_token, new_usdc_balance);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("reconcile"), caller.clone()),
+            SecurityTokenEvent::UsdcReconciled(caller.clone(), reconciliation.surplus),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("reconcile"), &caller, None, justification);
+
+        Ok(reconciliation.surplus)
+    }
+
+    // Admin function to recover non-USDC assets sent to the contract by mistake.
+    // Only the surplus above tracked proceeds, fees and commissions can be swept.
+    pub fn sweep_token(
+        env: Env,
+        caller: Address,
+        asset: Address,
+        to: Address,
+        amount: i128,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_TREASURY));
+        }
+
+        // USDC surplus is reconciled to the issuer instead
+        let metadata = Self::get_metadata(&env);
+        if asset == metadata.usdc_token {
+            return Err(Error::from_contract_error(ERR_CANNOT_SWEEP_USDC));
+        }
+
+        let reconciliation = Self::reconcile_asset(&env, &asset);
+        if amount <= 0 || amount > reconciliation.surplus {
+            return Err(Error::from_contract_error(ERR_INVALID_WITHDRAW_AMOUNT));
+        }
+
+        // Transfer from contract to recipient with balance verification
+        Self::transfer_out(&env, &asset, &to, amount)?;
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("sweep"), caller.clone(), asset.clone()),
+            SecurityTokenEvent::TokenSwept(caller.clone(), asset, to, amount),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("sweep"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Allow the issuer to withdraw proceeds to a destination (issuer only)
+    pub fn add_withdrawal_destination(
+        env: Env,
+        caller: Address,
+        destination: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        let destination_key = DataKey::WithdrawalDestination(destination.clone());
+        env.storage().persistent().set(&destination_key, &true);
+
+        // Extend TTL for the destination entry
+        Self::extend_persistent_ttl(&env, &destination_key);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("wdest"), caller.clone(), destination.clone()),
+            SecurityTokenEvent::WithdrawalDestinationAdded(destination.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("wdest"), &caller, Some(destination), justification);
+
+        Ok(())
+    }
+
+    // Remove a destination from the withdrawal allowlist (issuer only)
+    pub fn remove_withdrawal_destination(
+        env: Env,
+        caller: Address,
+        destination: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::WithdrawalDestination(destination.clone()));
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("wdestrem"), caller.clone(), destination.clone()),
+            SecurityTokenEvent::WithdrawalDestinationRemoved(destination.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("wdestrem"), &caller, Some(destination), justification);
+
+        Ok(())
+    }
+
+    // Configure a primary offering window with soft and hard caps (issuer only)
+    pub fn configure_offering(
+        env: Env,
+        caller: Address,
+        start: u64,
+        end: u64,
+        soft_cap: i128,
+        hard_cap: i128,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        // Validate window and caps
+        if start >= end || end <= env.ledger().timestamp() || soft_cap <= 0 || soft_cap > hard_cap {
+            return Err(Error::from_contract_error(ERR_INVALID_OFFERING));
+        }
+
+        // A running or unrefunded offering cannot be replaced
+        let id = match Self::offering(env.clone()) {
+            Some(offering) => {
+                Self::require_offering_settled(&env, &offering)?;
+                offering.id + 1
+            }
+            None => 0,
+        };
+
+        let offering = Offering {
+            id,
+            start,
+            end,
+            soft_cap,
+            hard_cap,
+            raised: 0,
+            fees: 0,
+            commissions: 0,
+        };
+        env.storage().instance().set(&OFFERING_KEY, &offering);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("offering"), caller.clone()),
+            SecurityTokenEvent::OfferingConfigured(id, start, end, soft_cap, hard_cap),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("offering"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Remove a settled offering so purchases are open-ended again (issuer only)
+    pub fn clear_offering(
+        env: Env,
+        caller: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        let offering = Self::offering(env.clone())
+            .ok_or(Error::from_contract_error(ERR_INVALID_OFFERING))?;
+        Self::require_offering_settled(&env, &offering)?;
+
+        env.storage().instance().remove(&OFFERING_KEY);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("offerrem"), caller.clone()),
+            SecurityTokenEvent::OfferingCleared(offering.id),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("offerrem"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Refund an investor's USDC and return their tokens to the issuer after a failed offering
+    pub fn claim_refund(env: Env, buyer: Address, beneficiary: Address) -> Result<(), Error> {
+        buyer.require_auth();
+        Self::refund_position(&env, &buyer, &beneficiary)
+    }
+
+    // Push a refund to a buyer who has not claimed it, so an absent investor cannot hold the
+    // offering open. Anyone can call this; the USDC always goes back to the buyer who paid.
+    pub fn refund_investor(env: Env, buyer: Address, beneficiary: Address) -> Result<(), Error> {
+        Self::refund_position(&env, &buyer, &beneficiary)
+    }
+    // Set the investor classification for an address
+    pub fn set_investor_class(
+        env: Env,
+        caller: Address,
+        address: Address,
+        class: InvestorClass,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_INVESTOR));
+        }
+
+        // Update classification in PERSISTENT storage
+        let class_key = DataKey::InvestorClass(address.clone());
+        env.storage()
+            .persistent()
+            .set(&class_key, &class);
+
+        // Extend TTL for the classification entry
+        Self::extend_persistent_ttl(&env, &class_key);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("invclass"), caller.clone(), address.clone()),
+            SecurityTokenEvent::InvestorClassUpdated(address.clone(), class),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("invclass"), &caller, Some(address), justification);
+
+        Ok(())
+    }
+
+    // Set USDC investment limits for an investor class
+    pub fn set_investment_limits(
+        env: Env,
+        caller: Address,
+        class: InvestorClass,
+        limits: InvestmentLimits,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_INVESTOR));
+        }
+
+        // Validate limits
+        if limits.min_purchase < 0 || limits.max_purchase < 0 || limits.max_total < 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_LIMITS));
+        }
+        if limits.max_purchase > 0 && limits.min_purchase > limits.max_purchase {
+            return Err(Error::from_contract_error(ERR_INVALID_LIMITS));
+        }
+        if limits.max_total > 0 && limits.min_purchase > limits.max_total {
+            return Err(Error::from_contract_error(ERR_INVALID_LIMITS));
+        }
+
+        // Update limits in PERSISTENT storage
+        let limits_key = DataKey::InvestmentLimits(class.clone());
+        env.storage()
+            .persistent()
+            .set(&limits_key, &limits);
+
+        // Extend TTL for the limits entry
+        Self::extend_persistent_ttl(&env, &limits_key);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("limits"), caller.clone()),
+            SecurityTokenEvent::InvestmentLimitsUpdated(class, limits),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("limits"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Configure the platform fee charged on purchases
+    pub fn set_fee_config(
+        env: Env,
+        caller: Address,
+        config: FeeConfig,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let is_admin = Self::is_admin(&env, &caller);
+        match Self::fee_config(env.clone()) {
+            // The current recipient hands the fee stream over; admins can rotate a lost
+            // recipient key only with a justification on the audit record
+            Some(current) if current.recipient != config.recipient => {
+                if caller != current.recipient && !(is_admin && justification.is_some()) {
+                    return Err(Error::from_contract_error(ERR_NOT_FEE_RECIPIENT));
+                }
+                if !is_admin && (current.fee_bps != config.fee_bps || current.mode != config.mode) {
+                    return Err(Error::from_contract_error(ERR_NOT_ADMIN_FEE));
+                }
+                // Escrowed fees are reversed against the recipient that charged them
+                if Self::escrowed_fees(&env) > 0 {
+                    return Err(Error::from_contract_error(ERR_USDC_IN_ESCROW));
+                }
+            }
+            _ => {
+                // Check if caller is admin
+                if !is_admin {
+                    return Err(Error::from_contract_error(ERR_NOT_ADMIN_FEE));
+                }
+            }
+        }
+
+        // Validate fee, leaving room for the highest approved broker commission
+        if config.fee_bps as i128 + Self::max_commission_bps(&env) as i128 > BPS_DENOMINATOR {
+            return Err(Error::from_contract_error(ERR_INVALID_BPS));
+        }
+
+        env.storage().instance().set(&FEE_CFG_KEY, &config);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("feecfg"), caller.clone(), config.recipient.clone()),
+            SecurityTokenEvent::FeeConfigUpdated(config.recipient, config.fee_bps),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("feecfg"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Fee recipient function to withdraw accrued platform fees
+    pub fn withdraw_fees(
+        env: Env,
+        caller: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Fees stay with the recipient that earned them, including past recipients
+        let accrued_fees = Self::accrued_fees(env.clone(), caller.clone(), asset.clone());
+        let is_recipient = Self::fee_config(env.clone())
+            .is_some_and(|config| config.recipient == caller);
+        if !is_recipient && accrued_fees == 0 {
+            return Err(Error::from_contract_error(ERR_NOT_FEE_RECIPIENT));
+        }
+
+        // Validate amount
+        if amount <= 0 || amount > accrued_fees {
+            return Err(Error::from_contract_error(ERR_INVALID_WITHDRAW_AMOUNT));
+        }
+
+        // Fees on USDC raised below the offering soft cap stay in escrow
+        let metadata = Self::get_metadata(&env);
+        let escrowed_fees = if is_recipient { Self::escrowed_fees(&env) } else { 0 };
+        if asset == metadata.usdc_token && amount > accrued_fees - escrowed_fees {
+            return Err(Error::from_contract_error(ERR_USDC_IN_ESCROW));
+        }
+
+        // Transfer from contract to fee recipient with balance verification
+        Self::transfer_out(&env, &asset, &caller, amount)?;
+
+        // Update accrued fees
+        Self::set_accrued_fees(&env, &caller, &asset, accrued_fees - amount);
+
+        // Emit withdrawal event
+        Self::publish_event(
+            &env,
+            (symbol_short!("feewd"), caller.clone(), asset.clone()),
+            SecurityTokenEvent::FeesWithdrawn(caller.clone(), asset, amount),
+        );
+
+        Ok(())
+    }
+
+    // Approve a broker-dealer to refer purchases, or update their commission
+    pub fn approve_broker(
+        env: Env,
+        caller: Address,
+        broker: Address,
+        commission_bps: u32,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_BROKER));
+        }
+
+        // Validate commission together with the platform fee charged on the same payment
+        let fee_bps = Self::fee_config(env.clone()).map_or(0, |config| config.fee_bps);
+        if commission_bps as i128 + fee_bps as i128 > BPS_DENOMINATOR {
+            return Err(Error::from_contract_error(ERR_INVALID_BPS));
+        }
+
+        // Track approved commission rates so fee changes can be validated against them
+        if let Some(previous) = Self::broker(env.clone(), broker.clone()).filter(|info| info.approved) {
+            Self::adjust_commission_rate(&env, previous.commission_bps, -1);
+        }
+        Self::adjust_commission_rate(&env, commission_bps, 1);
+
+        let broker_key = DataKey::Broker(broker.clone());
+        env.storage().persistent().set(
+            &broker_key,
+            &BrokerInfo {
+                commission_bps,
+                approved: true,
+            },
+        );
+
+        // Extend TTL for the broker entry
+        Self::extend_persistent_ttl(&env, &broker_key);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("broker"), caller.clone(), broker.clone()),
+            SecurityTokenEvent::BrokerApproved(broker.clone(), commission_bps),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("broker"), &caller, Some(broker), justification);
+
+        Ok(())
+    }
+
+    // Revoke a broker's approval; already accrued commission stays claimable
+    pub fn revoke_broker(
+        env: Env,
+        caller: Address,
+        broker: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_BROKER));
+        }
+
+        let mut info = Self::broker(env.clone(), broker.clone())
+            .ok_or(Error::from_contract_error(ERR_NOT_APPROVED_BROKER))?;
+        if info.approved {
+            Self::adjust_commission_rate(&env, info.commission_bps, -1);
+        }
+        info.approved = false;
+        env.storage().persistent().set(&DataKey::Broker(broker.clone()), &info);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("brokerrev"), caller.clone(), broker.clone()),
+            SecurityTokenEvent::BrokerRevoked(broker.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("brokerrev"), &caller, Some(broker), justification);
+
+        Ok(())
+    }
+
+    // Broker function to claim accrued commission in a payment asset
+    pub fn claim_commission(env: Env, broker: Address, asset: Address) -> Result<(), Error> {
+        broker.require_auth();
+
+        let sales_key = DataKey::BrokerSales(broker.clone(), asset.clone());
+        let mut sales = Self::broker_sales(env.clone(), broker.clone(), asset.clone());
+
+        // Commissions on USDC raised below the offering soft cap stay in escrow
+        let metadata = Self::get_metadata(&env);
+        let mut amount = sales.accrued;
+        if asset == metadata.usdc_token {
+            if let Some(offering) = Self::offering(env.clone()) {
+                if offering.raised < offering.soft_cap {
+                    let escrowed: i128 = env
+                        .storage()
+                        .persistent()
+                        .get(&DataKey::EscrowedCommission(offering.id, broker.clone()))
+                        .unwrap_or(0);
+                    amount -= escrowed;
+                }
+            }
+        }
+
+        if amount <= 0 {
+            return Err(Error::from_contract_error(ERR_NOTHING_TO_CLAIM));
+        }
+
+        // Transfer from contract to broker with balance verification
+        Self::transfer_out(&env, &asset, &broker, amount)?;
+
+        // Update broker totals
+        Self::adjust_unclaimed_commissions(&env, &asset, -amount);
+        sales.accrued -= amount;
+        sales.claimed = sales.claimed.checked_add(amount)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        env.storage().persistent().set(&sales_key, &sales);
+        Self::extend_persistent_ttl(&env, &sales_key);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("commclaim"), broker.clone(), asset.clone()),
+            SecurityTokenEvent::CommissionClaimed(broker.clone(), asset, amount),
+        );
+
+        Ok(())
+    }
+
+    // Register or update an accepted payment asset with its price and decimals
+    pub fn set_payment_asset(
+        env: Env,
+        caller: Address,
+        asset: Address,
+        price: i128,
+        decimals: u32,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_PAYMENT_ASSET));
+        }
+
+        // USDC is priced through usdc_price and cannot be registered here
+        let metadata = Self::get_metadata(&env);
+        if asset == metadata.usdc_token || asset == env.current_contract_address() {
+            return Err(Error::from_contract_error(ERR_INVALID_PAYMENT_ASSET));
+        }
+
+        // Validate price bounds (same as constructor)
+        if price <= 0 || price > MAX_USDC_PRICE {
+            return Err(Error::from_contract_error(ERR_INVALID_PRICE));
+        }
+
+        // Price is quoted in the asset's smallest unit, so decimals must match the asset
+        if decimals != token::Client::new(&env, &asset).decimals() {
+            return Err(Error::from_contract_error(ERR_INVALID_PAYMENT_ASSET));
+        }
+
+        // Add to the registry if not already present
+        let mut assets = Self::payment_assets(env.clone());
+        if !assets.contains(&asset) {
+            assets.push_back(asset.clone());
+            env.storage().instance().set(&PAY_ASSETS_KEY, &assets);
+        }
+
+        // Store asset pricing in PERSISTENT storage
+        let asset_key = DataKey::PaymentAsset(asset.clone());
+        env.storage()
+            .persistent()
+            .set(&asset_key, &PaymentAsset { price, decimals });
+
+        // Extend TTLs
+        Self::extend_persistent_ttl(&env, &asset_key);
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("payasset"), caller.clone(), asset.clone()),
+            SecurityTokenEvent::PaymentAssetSet(asset, price, decimals),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("payasset"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Register or update a legal document (offering memorandum, appraisal, ...) by name
+    pub fn set_document(
+        env: Env,
+        caller: Address,
+        name: Symbol,
+        uri: String,
+        document_hash: BytesN<32>,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_DOCUMENT));
+        }
+
+        // Validate URI
+        if uri.is_empty() || uri.len() > MAX_DOCUMENT_URI_LEN {
+            return Err(Error::from_contract_error(ERR_INVALID_DOCUMENT));
+        }
+
+        // Add to the document list if new
+        let mut names = Self::all_documents(env.clone());
+        if !names.contains(&name) {
+            if names.len() >= MAX_DOCUMENTS {
+                return Err(Error::from_contract_error(ERR_INVALID_DOCUMENT));
+            }
+            names.push_back(name.clone());
+            env.storage().instance().set(&DOCUMENTS_KEY, &names);
+        }
+
+        // Store document in PERSISTENT storage
+        let document_key = DataKey::Document(name.clone());
+        env.storage().persistent().set(
+            &document_key,
+            &Document {
+                uri: uri.clone(),
+                document_hash: document_hash.clone(),
+                updated_at: env.ledger().timestamp(),
+            },
+        );
+
+        // Extend TTLs
+        Self::extend_persistent_ttl(&env, &document_key);
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("docset"), caller.clone(), name.clone()),
+            DisclosureEvent::DocumentUpdated(name, uri, document_hash),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("docset"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Require buyers to have accepted this subscription agreement version before purchasing
+    pub fn set_active_terms(
+        env: Env,
+        caller: Address,
+        document_hash: BytesN<32>,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_TERMS));
+        }
+
+        env.storage().instance().set(&TERMS_KEY, &document_hash);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("terms"), caller.clone()),
+            DisclosureEvent::ActiveTermsSet(caller.clone(), document_hash),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("terms"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Stop requiring terms acceptance for purchases
+    pub fn clear_active_terms(
+        env: Env,
+        caller: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_TERMS));
+        }
+
+        env.storage().instance().remove(&TERMS_KEY);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("termsrem"), caller.clone()),
+            DisclosureEvent::ActiveTermsCleared(caller.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("termsrem"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Record that an investor accepted a subscription agreement version
+    pub fn accept_terms(env: Env, investor: Address, document_hash: BytesN<32>) {
+        investor.require_auth();
+
+        // Store the latest acceptance in PERSISTENT storage
+        let acceptance_key = DataKey::TermsAcceptance(investor.clone());
+        env.storage().persistent().set(
+            &acceptance_key,
+            &TermsAcceptance {
+                document_hash: document_hash.clone(),
+                accepted_at: env.ledger().timestamp(),
+            },
+        );
+        Self::extend_persistent_ttl(&env, &acceptance_key);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("termsacc"), investor.clone()),
+            DisclosureEvent::TermsAccepted(investor, document_hash),
+        );
+    }
+
+    // Remove a legal document
+    pub fn remove_document(
+        env: Env,
+        caller: Address,
+        name: Symbol,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_DOCUMENT));
+        }
+
+        let mut names = Self::all_documents(env.clone());
+        let index = names
+            .first_index_of(&name)
+            .ok_or(Error::from_contract_error(ERR_DOCUMENT_NOT_FOUND))?;
+        names.remove(index);
+        env.storage().instance().set(&DOCUMENTS_KEY, &names);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Document(name.clone()));
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("docrem"), caller.clone(), name.clone()),
+            DisclosureEvent::DocumentRemoved(name),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("docrem"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Stop accepting a payment asset; its tracked balance stays withdrawable
+    pub fn remove_payment_asset(
+        env: Env,
+        caller: Address,
+        asset: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_PAYMENT_ASSET));
+        }
+
+        let mut assets = Self::payment_assets(env.clone());
+        let index = assets
+            .first_index_of(&asset)
+            .ok_or(Error::from_contract_error(ERR_ASSET_NOT_ACCEPTED))?;
+        assets.remove(index);
+        env.storage().instance().set(&PAY_ASSETS_KEY, &assets);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PaymentAsset(asset.clone()));
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("payassetr"), caller.clone(), asset.clone()),
+            SecurityTokenEvent::PaymentAssetRemoved(asset),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("payassetr"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Update name, symbol and/or home domain (issuer only). Locked fields cannot change.
+    pub fn update_metadata(
+        env: Env,
+        caller: Address,
+        name: Option<String>,
+        symbol: Option<String>,
+        home_domain: Option<String>,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        let mut metadata = Self::get_metadata(&env);
+        let mut changes = Vec::new(&env);
+
+        // Validate parameters (same limits as constructor)
+        if let Some(name) = name {
+            if name.is_empty() || name.len() > MAX_NAME_LEN {
+                return Err(Error::from_contract_error(ERR_INVALID_METADATA));
+            }
+            changes.push_back((MetadataField::Name, metadata.name.clone(), name.clone()));
+            metadata.name = name;
+        }
+        if let Some(symbol) = symbol {
+            if symbol.is_empty() || symbol.len() > MAX_SYMBOL_LEN {
+                return Err(Error::from_contract_error(ERR_INVALID_METADATA));
+            }
+            changes.push_back((MetadataField::Symbol, metadata.symbol.clone(), symbol.clone()));
+            metadata.symbol = symbol;
+        }
+        if let Some(home_domain) = home_domain {
+            if home_domain.is_empty() || home_domain.len() > MAX_HOME_DOMAIN_LEN {
+                return Err(Error::from_contract_error(ERR_INVALID_METADATA));
+            }
+            changes.push_back((MetadataField::HomeDomain, metadata.home_domain.clone(), home_domain.clone()));
+            metadata.home_domain = home_domain;
+        }
+
+        if changes.is_empty() {
+            return Err(Error::from_contract_error(ERR_INVALID_METADATA));
+        }
+
+        // Reject changes to locked fields
+        let locks = Self::metadata_locks(env.clone());
+        for (field, _, _) in changes.iter() {
+            if locks.contains(&field) {
+                return Err(Error::from_contract_error(ERR_METADATA_LOCKED));
+            }
+        }
+
+        // Update metadata in INSTANCE storage
+        env.storage().instance().set(&METADATA_KEY, &metadata);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit an event per changed field
+        for (field, previous, new) in changes.iter() {
+            Self::publish_event(
+                &env,
+                (symbol_short!("metadata"), caller.clone()),
+                DisclosureEvent::MetadataUpdated(field, previous, new),
+            );
+        }
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("metadata"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Permanently lock a metadata field against further updates (issuer only)
+    pub fn lock_metadata_field(
+        env: Env,
+        caller: Address,
+        field: MetadataField,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        let mut locks = Self::metadata_locks(env.clone());
+        if locks.contains(&field) {
+            return Err(Error::from_contract_error(ERR_METADATA_LOCKED));
+        }
+        locks.push_back(field.clone());
+        env.storage().instance().set(&META_LOCKS_KEY, &locks);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("metalock"), caller.clone()),
+            DisclosureEvent::MetadataFieldLocked(field),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("metalock"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Set transfer restriction flag
+    pub fn set_transfer_restriction(
+        env: Env,
+        caller: Address,
+        restricted: bool,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_TRANSFER_RESTRICTION));
+        }
+
+        // Update configuration in INSTANCE storage
+        let mut config = Self::get_config(&env);
+        config.transfer_restricted = restricted;
+        env.storage().instance().set(&CONFIG_KEY, &config);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit transfer restriction changed event
+        Self::publish_event(
+            &env,
+            (symbol_short!("restrict"), caller.clone()),
+            SecurityTokenEvent::TransferRestrictionChanged(caller.clone(), restricted),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("restrict"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Grant the pricing role to an address (issuer only)
+    pub fn add_pricer(
+        env: Env,
+        caller: Address,
+        pricer: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer (only issuer can manage the pricing role)
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        // Get current pricer list from INSTANCE storage
+        let mut pricers: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&PRICERS_KEY)
+            .unwrap_or(Vec::new(&env));
+
+        if pricers.contains(&pricer) {
+            return Err(Error::from_contract_error(ERR_DUPLICATE_PRICER));
+        }
+
+        pricers.push_back(pricer.clone());
+        env.storage().instance().set(&PRICERS_KEY, &pricers);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit pricer added event
+        Self::publish_event(
+            &env,
+            (symbol_short!("pricer"), caller.clone(), pricer.clone()),
+            SecurityTokenEvent::PricerAdded(caller.clone(), pricer.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("pricer"), &caller, Some(pricer), justification);
+
+        Ok(())
+    }
+
+    // Revoke the pricing role from an address (issuer only)
+    pub fn remove_pricer(
+        env: Env,
+        caller: Address,
+        pricer: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        let mut pricers: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&PRICERS_KEY)
+            .unwrap_or(Vec::new(&env));
+
+        let index = pricers
+            .first_index_of(&pricer)
+            .ok_or(Error::from_contract_error(ERR_NOT_A_PRICER))?;
+        pricers.remove(index);
+        env.storage().instance().set(&PRICERS_KEY, &pricers);
+
+        // Emit pricer removed event
+        Self::publish_event(
+            &env,
+            (symbol_short!("pricerrem"), caller.clone(), pricer.clone()),
+            SecurityTokenEvent::PricerRemoved(caller.clone(), pricer.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("pricerrem"), &caller, Some(pricer), justification);
+
+        Ok(())
+    }
+
+    // Set the maximum allowed price change per update in basis points (0 disables the guard)
+    pub fn set_max_price_change(
+        env: Env,
+        caller: Address,
+        max_change_bps: u32,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is issuer
+        if !Self::is_issuer(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        if max_change_bps as i128 > BPS_DENOMINATOR {
+            return Err(Error::from_contract_error(ERR_INVALID_BPS));
+        }
+
+        env.storage().instance().set(&PRICE_MAX_KEY, &max_change_bps);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("pricemax"), caller.clone()),
+            SecurityTokenEvent::MaxPriceChangeUpdated(max_change_bps),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("pricemax"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Update the token price in USDC (pricing role or issuer)
+    pub fn set_price(
+        env: Env,
+        caller: Address,
+        new_price: i128,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller holds the pricing role
+        if !Self::has_pricing_role(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_PRICER));
+        }
+
+        // Validate price bounds (same as constructor)
+        if new_price <= 0 || new_price > MAX_USDC_PRICE {
+            return Err(Error::from_contract_error(ERR_INVALID_PRICE));
+        }
+
+        let mut metadata = Self::get_metadata(&env);
+        let previous_price = metadata.usdc_price;
+
+        // Guard against fat-finger updates when a maximum change is configured
+        let max_change_bps = Self::max_price_change(env.clone());
+        if max_change_bps > 0 {
+            let change = (new_price - previous_price).abs();
+            let allowed = previous_price.checked_mul(max_change_bps as i128)
+                .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+                / BPS_DENOMINATOR;
+            if change > allowed {
+                return Err(Error::from_contract_error(ERR_PRICE_CHANGE_TOO_LARGE));
+            }
+        }
+
+        // Update price in INSTANCE storage
+        metadata.usdc_price = new_price;
+        env.storage().instance().set(&METADATA_KEY, &metadata);
+
+        // Append to price history
+        Self::record_price_change(&env, &caller, previous_price, new_price);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit price updated event
+        Self::publish_event(
+            &env,
+            (symbol_short!("price"), caller.clone()),
+            SecurityTokenEvent::PriceUpdated(caller.clone(), previous_price, new_price),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("price"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Price purchases from a SEP-40 oracle instead of the static price (pricing role or issuer)
+    pub fn set_oracle(
+        env: Env,
+        caller: Address,
+        config: OracleConfig,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller holds the pricing role
+        if !Self::has_pricing_role(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_PRICER));
+        }
+
+        // Validate oracle configuration
+        if config.oracle == env.current_contract_address() || config.max_age == 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_ORACLE_CONFIG));
+        }
+
+        env.storage().instance().set(&ORACLE_KEY, &config);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(&env);
+
+        // Emit oracle updated event
+        Self::publish_event(
+            &env,
+            (symbol_short!("oracle"), caller.clone()),
+            SecurityTokenEvent::OracleUpdated(caller.clone(), config.oracle),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("oracle"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Revert to the static price (pricing role or issuer)
+    pub fn clear_oracle(
+        env: Env,
+        caller: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller holds the pricing role
+        if !Self::has_pricing_role(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_PRICER));
+        }
+
+        env.storage().instance().remove(&ORACLE_KEY);
+
+        // Emit oracle cleared event
+        Self::publish_event(
+            &env,
+            (symbol_short!("oraclerem"), caller.clone()),
+            SecurityTokenEvent::OracleCleared(caller.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("oraclerem"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Admin function to extend instance storage TTL on-demand
+    pub fn bump_instance_ttl(
+        env: Env,
+        caller: Address,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_TTL));
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("bumpinst"), caller.clone()),
+            SecurityTokenEvent::InstanceTtlBumped(caller.clone()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("bumpinst"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // Admin function to extend TTLs for multiple user addresses (bulk operation)
+    pub fn bump_user_ttls(
+        env: Env,
+        caller: Address,
+        addresses: Vec<Address>,
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        // Check if caller is admin
+        if !Self::is_admin(&env, &caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ADMIN_TTL));
+        }
+
+        for address in addresses.iter() {
+            // Extend balance TTL if exists
+            let balance_key = DataKey::Balance(address.clone());
+            Self::extend_persistent_ttl(&env, &balance_key);
+
+            // Extend KYC TTL if exists
+            let kyc_key = DataKey::KycVerified(address.clone());
+            Self::extend_persistent_ttl(&env, &kyc_key);
+
+            // Extend compliance TTL if exists
+            let compliance_key = DataKey::ComplianceStatus(address.clone());
+            Self::extend_persistent_ttl(&env, &compliance_key);
+
+            // Extend split epoch TTL if exists
+            let epoch_key = DataKey::SplitEpoch(address.clone());
+            Self::extend_persistent_ttl(&env, &epoch_key);
+
+            // Extend holder index TTLs if the address holds tokens
+            let position_key = DataKey::HolderPosition(address.clone());
+            if let Some(position) = env.storage().persistent().get::<DataKey, u32>(&position_key) {
+                Self::extend_persistent_ttl(&env, &position_key);
+                Self::extend_persistent_ttl(&env, &DataKey::HolderIndex(position));
+            }
+        }
+
+        // Emit event
+        Self::publish_event(
+            &env,
+            (symbol_short!("bumpusers"), caller.clone()),
+            SecurityTokenEvent::UserTtlsBumped(caller.clone(), addresses.len()),
+        );
+
+        // Record audit log entry
+        Self::record_audit(&env, symbol_short!("bumpusers"), &caller, None, justification);
+
+        Ok(())
+    }
+
+    // View function to get token metadata
+    pub fn get_metadata(env: &Env) -> TokenMetadata {
+        env.storage()
+            .instance()
+            .get(&METADATA_KEY)
+            .expect("Token not initialized")
+    }
+
+    // View function to get balance
+    pub fn balance(env: Env, address: Address) -> i128 {
+        let balance = env.storage()
+            .persistent()
+            .get(&DataKey::Balance(address.clone()))
+            .unwrap_or(INITIAL_BALANCE);
+        Self::apply_splits(&env, &address, balance)
+    }
+
+    // View function to get a balance within a partition ("default" holds unpartitioned units)
+    pub fn balance_of_by_partition(env: Env, address: Address, partition: Symbol) -> i128 {
+        if partition == DEFAULT_PARTITION {
+            return Self::default_balance(&env, &address);
+        }
+        let balance = env.storage()
+            .persistent()
+            .get(&DataKey::PartitionBalance(address.clone(), partition))
+            .unwrap_or(INITIAL_BALANCE);
+        Self::apply_splits(&env, &address, balance)
+    }
+
+    // View function to get the migration record, if a successor is registered
+    pub fn migration(env: Env) -> Option<Migration> {
+        env.storage().instance().get(&MIGRATION_KEY)
+    }
+
+    // View function to check if every unit has moved to the successor
+    pub fn is_migration_complete(env: Env) -> bool {
+        Self::migration(env.clone()).is_some() && Self::get_metadata(&env).total_supply == 0
+    }
+
+    // View function to list splits in the order they were applied
+    pub fn splits(env: Env) -> Vec<StockSplit> {
+        env.storage().instance().get(&SPLITS_KEY).unwrap_or(Vec::new(&env))
+    }
+
+    // View function to get the wind-down state, if the token is being wound down
+    pub fn wind_down(env: Env) -> Option<WindDown> {
+        env.storage().instance().get(&WIND_DOWN_KEY)
+    }
+
+    // View function to check if the wind-down has retired the entire supply
+    pub fn is_fully_retired(env: Env) -> bool {
+        Self::wind_down(env.clone()).is_some() && Self::get_metadata(&env).total_supply == 0
+    }
+
+    // View function to get the debt terms, if the token is in debt mode
+    pub fn debt_terms(env: Env) -> Option<DebtTerms> {
+        env.storage().instance().get(&DEBT_KEY)
+    }
+
+    // View function to get the coupon state, if the token is in debt mode
+    pub fn coupon_state(env: Env) -> Option<CouponState> {
+        env.storage().instance().get(&COUPON_KEY)
+    }
+
+    // View function to get a holder's coupon entitlement, including unsettled accrual
+    pub fn accrued_coupon(env: Env, holder: Address) -> Result<i128, Error> {
+        let coupon = match Self::due_coupon_state(&env)? {
+            Some(coupon) => coupon,
+            None => return Ok(0),
+        };
+        let account = Self::coupon_account(env.clone(), holder.clone());
+        let balance = Self::coupon_bearing_balance(&env, &holder);
+        Self::accrue_coupon(balance, &account, &coupon)
+    }
+
+    // View function to list registered partitions (excluding "default")
+    pub fn partitions(env: Env) -> Vec<Symbol> {
+        env.storage()
+            .instance()
+            .get(&PARTITIONS_KEY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // View function to get the rules for a registered partition
+    pub fn partition_rules(env: Env, partition: Symbol) -> Option<PartitionRules> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Partition(partition))
+    }
+
+    // View function to check compliance status
+    pub fn check_compliance(env: Env, address: Address) -> ComplianceStatus {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ComplianceStatus(address))
+            .unwrap_or(ComplianceStatus::Pending)
+    }
+
+    // View function to check KYC status
+    pub fn is_kyc_verified(env: Env, address: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::KycVerified(address))
+            .unwrap_or(false)
+    }
+
+    // View function to check accumulated USDC balance
+    pub fn usdc_balance(env: Env) -> i128 {
+        let metadata = Self::get_metadata(&env);
+        Self::asset_balance(env, metadata.usdc_token)
+    }
+
+    // View function to check the accumulated balance of a payment asset
+    pub fn asset_balance(env: Env, asset: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AssetBalance(asset))
+            .unwrap_or(INITIAL_BALANCE)
+    }
+
+    // View function to quote the exact USDC due for a token amount at the current price
+    pub fn quote_purchase(env: Env, token_amount: i128) -> Result<i128, Error> {
+        if token_amount <= 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_PURCHASE_AMOUNT));
+        }
+        let metadata = Self::get_metadata(&env);
+        let usdc_price = Self::resolve_price(&env, &metadata)?;
+        Self::calculate_cost(&metadata, token_amount, usdc_price)
+    }
+
+    // View function to get the investor classification of an address
+    pub fn investor_class(env: Env, address: Address) -> InvestorClass {
+        env.storage()
+            .persistent()
+            .get(&DataKey::InvestorClass(address))
+            .unwrap_or(InvestorClass::Retail)
+    }
+
+    // View function to get the investment limits for an investor class, if any
+    pub fn investment_limits(env: Env, class: InvestorClass) -> Option<InvestmentLimits> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::InvestmentLimits(class))
+    }
+
+    // View function to get the cumulative USDC invested for a beneficiary
+    pub fn total_invested(env: Env, address: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TotalInvested(address))
+            .unwrap_or(INITIAL_BALANCE)
+    }
+
+    // View function to get the platform fee configuration, if any
+    pub fn fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage().instance().get(&FEE_CFG_KEY)
+    }
+
+    // View function to get platform fees accrued to a recipient in a payment asset
+    pub fn accrued_fees(env: Env, recipient: Address, asset: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AccruedFees(recipient, asset))
+            .unwrap_or(INITIAL_BALANCE)
+    }
+
+    // View function to get platform fees accrued to all recipients in a payment asset
+    pub fn total_accrued_fees(env: Env, asset: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TotalAccruedFees(asset))
+            .unwrap_or(INITIAL_BALANCE)
+    }
+
+    // View function to get the total commissions owed to brokers in a payment asset
+    pub fn unclaimed_commissions(env: Env, asset: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UnclaimedCommissions(asset))
+            .unwrap_or(INITIAL_BALANCE)
+    }
+
+    // View function to compare tracked USDC liabilities with the contract's USDC balance
+    pub fn usdc_reconciliation(env: Env) -> Reconciliation {
+        let metadata = Self::get_metadata(&env);
+        Self::reconcile_asset(&env, &metadata.usdc_token)
+    }
+
+    // View function to get a broker's approval and commission rate, if registered
+    pub fn broker(env: Env, broker: Address) -> Option<BrokerInfo> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Broker(broker))
+    }
+
+    // View function to get a broker's referred sales and commissions for a payment asset
+    pub fn broker_sales(env: Env, broker: Address, asset: Address) -> BrokerSales {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BrokerSales(broker, asset))
+            .unwrap_or(BrokerSales {
+                volume: 0,
+                tokens_sold: 0,
+                accrued: 0,
+                claimed: 0,
+            })
+    }
+
+    // View function to check if proceeds may be withdrawn to an address
+    pub fn is_withdrawal_destination(env: Env, address: Address) -> bool {
+        Self::is_issuer(&env, &address)
+            || env
+                .storage()
+                .persistent()
+                .get(&DataKey::WithdrawalDestination(address))
+                .unwrap_or(false)
+    }
+
+    // View function to get the jurisdiction recorded for an address, if any
+    pub fn jurisdiction(env: Env, address: Address) -> Option<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Jurisdiction(address))
+    }
+
+    // View function to get the guardian, if appointed
+    pub fn guardian(env: Env) -> Option<Address> {
+        env.storage().instance().get(&GUARDIAN_KEY)
+    }
+
+    // View function to get the USDC withdrawal policy, if configured
+    pub fn withdrawal_policy(env: Env) -> Option<WithdrawalPolicy> {
+        env.storage().instance().get(&WD_POLICY_KEY)
+    }
+
+    // View function to get a queued withdrawal
+    pub fn queued_withdrawal(env: Env, id: u32) -> Option<QueuedWithdrawal> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::QueuedWithdrawal(id))
+    }
+
+    // View function to get the current offering, if any
+    pub fn offering(env: Env) -> Option<Offering> {
+        env.storage().instance().get(&OFFERING_KEY)
+    }
+
+    // View function to get a buyer's position for a beneficiary in the current offering
+    pub fn offering_position(env: Env, buyer: Address, beneficiary: Address) -> OfferingPosition {
+        let id = match Self::offering(env.clone()) {
+            Some(offering) => offering.id,
+            None => return Self::empty_position(&env),
+        };
+        env.storage()
+            .persistent()
+            .get(&DataKey::OfferingPosition(id, buyer, beneficiary))
+            .unwrap_or(Self::empty_position(&env))
+    }
+
+    // View function to get the tokens locked for a beneficiary until the offering settles
+    pub fn locked_allocation(env: Env, beneficiary: Address) -> i128 {
+        match Self::offering(env.clone()) {
+            Some(offering) if offering.raised < offering.soft_cap => env
+                .storage()
+                .persistent()
+                .get(&DataKey::OfferingAllocation(offering.id, beneficiary))
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    // View function to get the USDC held in escrow until the soft cap is met
+    pub fn escrowed_usdc(env: Env) -> i128 {
+        match Self::offering(env) {
+            Some(offering) if offering.raised < offering.soft_cap => offering.raised,
+            _ => 0,
+        }
+    }
+
+    // View function to list accepted non-USDC payment assets
+    pub fn payment_assets(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&PAY_ASSETS_KEY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // View function to get a legal document by name
+    pub fn get_document(env: Env, name: Symbol) -> Option<Document> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Document(name))
+    }
+
+    // View function to get the subscription agreement hash buyers must accept, if any
+    pub fn active_terms(env: Env) -> Option<BytesN<32>> {
+        env.storage().instance().get(&TERMS_KEY)
+    }
+
+    // View function to get an investor's latest terms acceptance, if any
+    pub fn terms_acceptance(env: Env, investor: Address) -> Option<TermsAcceptance> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TermsAcceptance(investor))
+    }
+
+    // View function to list the names of all registered legal documents
+    pub fn all_documents(env: Env) -> Vec<Symbol> {
+        env.storage()
+            .instance()
+            .get(&DOCUMENTS_KEY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // View function to get pricing for an accepted payment asset
+    pub fn payment_asset(env: Env, asset: Address) -> Option<PaymentAsset> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PaymentAsset(asset))
+    }
+
+    // View function to get token price in USDC
+    pub fn token_price(env: Env) -> i128 {
+        let metadata = Self::get_metadata(&env);
+        metadata.usdc_price
+    }
+
+    // View function to get the issuer address
+    pub fn get_issuer(env: Env) -> Address {
+        let metadata = Self::get_metadata(&env);
+        metadata.issuer
+    }
+
+    // View function to get the oracle configuration, if any
+    pub fn oracle_config(env: Env) -> Option<OracleConfig> {
+        env.storage().instance().get(&ORACLE_KEY)
+    }
+
+    // View function to get the price purchases are currently settled at
+    pub fn current_price(env: Env) -> Result<i128, Error> {
+        let metadata = Self::get_metadata(&env);
+        Self::resolve_price(&env, &metadata)
+    }
+
+    // View function to check if an address holds the pricing role
+    pub fn is_pricer(env: Env, address: Address) -> bool {
+        Self::has_pricing_role(&env, &address)
+    }
+
+    // View function to get the maximum price change per update in basis points
+    pub fn max_price_change(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&PRICE_MAX_KEY)
+            .unwrap_or(0)
+    }
+
+    // View function to get the number of recorded price changes
+    pub fn price_history_len(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&PRICE_CNT_KEY)
+            .unwrap_or(0)
+    }
+
+    // View function to page through the price history, oldest first
+    pub fn price_history(env: Env, start: u32, limit: u32) -> Vec<PriceChange> {
+        let count = Self::price_history_len(env.clone());
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+        let mut history = Vec::new(&env);
+        for index in start..end {
+            if let Some(change) = env.storage().persistent().get(&DataKey::PriceHistory(index)) {
+                history.push_back(change);
+            }
+        }
+        history
+    }
+
+    // View function to list permanently locked metadata fields
+    pub fn metadata_locks(env: Env) -> Vec<MetadataField> {
+        env.storage()
+            .instance()
+            .get(&META_LOCKS_KEY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // View function to get the number of admin audit log entries
+    pub fn audit_log_len(env: Env) -> u32 {
+        env.storage().instance().get(&AUDIT_CNT_KEY).unwrap_or(0)
+    }
+
+    // View function to read the admin audit log, paginated (limit capped at MAX_PAGE_SIZE)
+    pub fn audit_log(env: Env, start: u32, limit: u32) -> Vec<AuditEntry> {
+        let count = Self::audit_log_len(env.clone());
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+        let mut entries = Vec::new(&env);
+        for index in start..end {
+            if let Some(entry) = env.storage().persistent().get(&DataKey::AuditEntry(index)) {
+                entries.push_back(entry);
+            }
+        }
+        entries
+    }
+
+    // View function to get the number of audit log entries for a subject address
+    pub fn subject_audit_len(env: Env, subject: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SubjectAuditCount(subject))
+            .unwrap_or(0)
+    }
+
+    // View function to read the audit log entries for a subject address, paginated
+    pub fn subject_audit_log(env: Env, subject: Address, start: u32, limit: u32) -> Vec<AuditEntry> {
+        let count = Self::subject_audit_len(env.clone(), subject.clone());
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+        let mut entries = Vec::new(&env);
+        for subject_index in start..end {
+            let index: Option<u32> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::SubjectAuditEntry(subject.clone(), subject_index));
+            if let Some(entry) = index.and_then(|index| env.storage().persistent().get(&DataKey::AuditEntry(index))) {
+                entries.push_back(entry);
+            }
+        }
+        entries
+    }
+
+    // View function to get the version of the event schema emitted by this contract
+    pub fn event_schema_version(_env: Env) -> u32 {
+        EVENT_SCHEMA_VERSION
+    }
+
+    // View function to get the number of addresses with a non-zero balance
+    pub fn holder_count(env: Env) -> u32 {
+        env.storage().instance().get(&HOLDER_CNT_KEY).unwrap_or(0)
+    }
+
+    // View function to list holders, paginated (limit capped at MAX_PAGE_SIZE).
+    // Removing a holder moves the last holder into its slot, so order is not stable.
+    pub fn holders(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        let count = Self::holder_count(env.clone());
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+
+        let mut holders = Vec::new(&env);
+        for index in start..end {
+            if let Some(holder) = env.storage().persistent().get(&DataKey::HolderIndex(index)) {
+                holders.push_back(holder);
+            }
+        }
+        holders
+    }
+
+    // View function to get a holder's balance, KYC, compliance and freeze status
+    pub fn holder_info(env: Env, address: Address) -> HolderInfo {
+        let compliance = Self::check_compliance(env.clone(), address.clone());
+        HolderInfo {
+            address: address.clone(),
+            balance: Self::balance(env.clone(), address.clone()),
+            kyc_verified: Self::is_kyc_verified(env.clone(), address),
+            frozen: compliance == ComplianceStatus::Suspended,
+            compliance,
+        }
+    }
+
+    // Internal helper functions
+
+    // Helper to extend instance storage TTL
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND);
+    }
+
+    // Helper to extend persistent storage TTL for a specific key
+    fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+        if env.storage().persistent().has(key) {
+            env.storage()
+                .persistent()
+                .extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND);
+        }
+    }
+
+    // Helper to get config from storage
+    fn get_config(env: &Env) -> ContractConfig {
+        env.storage()
+            .instance()
+            .get(&CONFIG_KEY)
+            .expect("Contract not initialized")
+    }
+
+    // Helper to check if address is an admin
+    fn is_admin(env: &Env, address: &Address) -> bool {
+        let admins: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&ADMINS_KEY)
+            .unwrap_or(Vec::new(env));
+        
+        for admin in admins.iter() {
+            if &admin == address {
+                return true;
+            }
+        }
+        false
+    }
+
+    // Helper to check if address is the issuer
+    fn is_issuer(env: &Env, address: &Address) -> bool {
+        let metadata = Self::get_metadata(env);
+        &metadata.issuer == address
+    }
+
+    // Helper to check if address holds the pricing role (the issuer always does)
+    fn has_pricing_role(env: &Env, address: &Address) -> bool {
+        if Self::is_issuer(env, address) {
+            return true;
+        }
+        let pricers: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&PRICERS_KEY)
+            .unwrap_or(Vec::new(env));
+        pricers.contains(address)
+    }
+
+    // Helper to resolve the effective USDC price, consulting the oracle when configured
+    fn resolve_price(env: &Env, metadata: &TokenMetadata) -> Result<i128, Error> {
+        let config: OracleConfig = match env.storage().instance().get(&ORACLE_KEY) {
+            Some(config) => config,
+            None => return Ok(metadata.usdc_price),
+        };
+
+        let oracle_client = PriceOracleClient::new(env, &config.oracle);
+        let now = env.ledger().timestamp();
+        let price_data = match oracle_client.lastprice(&config.asset) {
+            // A price dated in the future is never treated as fresh
+            Some(data) if data.timestamp <= now && now - data.timestamp <= config.max_age => data,
+            stale => {
+                // Fall back to the static price or halt purchases
+                if config.on_stale == StalePriceAction::Halt {
+                    let error = if stale.is_some() {
+                        ERR_ORACLE_PRICE_STALE
+                    } else {
+                        ERR_ORACLE_PRICE_UNAVAILABLE
+                    };
+                    return Err(Error::from_contract_error(error));
+                }
+                return Ok(metadata.usdc_price);
+            }
+        };
+
+        // Convert from oracle decimals to the USDC token's smallest unit
+        let oracle_decimals_pow = DECIMAL_BASE.checked_pow(oracle_client.decimals())
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        let usdc_decimals_pow = DECIMAL_BASE
+            .checked_pow(token::Client::new(env, &metadata.usdc_token).decimals())
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+
+        let usdc_price = price_data.price.checked_mul(usdc_decimals_pow)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+            .checked_div(oracle_decimals_pow)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+
+        if usdc_price <= 0 || usdc_price > MAX_USDC_PRICE {
+            return Err(Error::from_contract_error(ERR_INVALID_PRICE));
+        }
+
+        Ok(usdc_price)
+    }
+
+    // Helper to set the tracked balance of a payment asset
+    fn set_asset_balance(env: &Env, asset: &Address, amount: i128) {
+        let balance_key = DataKey::AssetBalance(asset.clone());
+        env.storage().persistent().set(&balance_key, &amount);
+        Self::extend_persistent_ttl(env, &balance_key);
+    }
+
+    // Helper to purchase tokens with USDC at the current price
+    fn purchase_usdc(
+        env: &Env,
+        buyer: &Address,
+        beneficiary: &Address,
+        token_amount: i128,
+        max_usdc: Option<i128>,
+        referrer: &Option<Address>,
+    ) -> Result<(), Error> {
+        // Load metadata from instance storage
+        let metadata = Self::get_metadata(env);
+        let usdc_price = Self::resolve_price(env, &metadata)?;
+
+        let usdc_amount = Self::execute_purchase(
+            env,
+            &metadata,
+            buyer,
+            beneficiary,
+            token_amount,
+            &metadata.usdc_token,
+            usdc_price,
+            max_usdc,
+        )?;
+
+        // Enforce the offering window and caps, and record the investor's position
+        let fee = Self::calculate_fee(env, usdc_amount)?;
+        let referral = Self::calculate_commission(env, buyer, referrer, token_amount, usdc_amount)?;
+        let escrowed = Self::record_offering_purchase(
+            env,
+            buyer,
+            beneficiary,
+            token_amount,
+            usdc_amount,
+            fee,
+            &referral,
+        )?;
+
+        // Enforce investment limits for the beneficiary's class
+        Self::record_investment(env, beneficiary, usdc_amount)?;
+
+        // Split proceeds between the issuer, the platform fee and the referring broker
+        Self::credit_proceeds(env, &metadata.usdc_token, usdc_amount, fee, &referral, escrowed)?;
+
+        // Emit purchase event
+        Self::publish_event(
+            env,
+            (symbol_short!("purchase"), buyer.clone(), beneficiary.clone()),
+            SecurityTokenEvent::Purchase(buyer.clone(), beneficiary.clone(), token_amount, usdc_amount),
+        );
+
+        Ok(())
+    }
+
+    // Helper to enforce the offering window and caps and record the investor's position
+    fn record_offering_purchase(
+        env: &Env,
+        buyer: &Address,
+        beneficiary: &Address,
+        token_amount: i128,
+        usdc_amount: i128,
+        fee: i128,
+        referral: &Option<(Address, BrokerSales)>,
+    ) -> Result<bool, Error> {
+        let mut offering = match Self::offering(env.clone()) {
+            Some(offering) => offering,
+            None => return Ok(false),
+        };
+
+        // Check the offering is open
+        let now = env.ledger().timestamp();
+        if now < offering.start || now >= offering.end {
+            return Err(Error::from_contract_error(ERR_OFFERING_NOT_OPEN));
+        }
+
+        // Check the hard cap
+        offering.raised = offering.raised.checked_add(usdc_amount)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        if offering.raised > offering.hard_cap {
+            return Err(Error::from_contract_error(ERR_HARD_CAP_EXCEEDED));
+        }
+        offering.fees = offering.fees.checked_add(fee)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        if let Some((_, sales)) = referral {
+            offering.commissions = offering.commissions.checked_add(sales.accrued)
+                .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        }
+        env.storage().instance().set(&OFFERING_KEY, &offering);
+
+        // Record USDC paid by the buyer and tokens allocated to the beneficiary together
+        let position_key = DataKey::OfferingPosition(offering.id, buyer.clone(), beneficiary.clone());
+        let mut position = Self::offering_position(env.clone(), buyer.clone(), beneficiary.clone());
+        position.contributed = position.contributed.checked_add(usdc_amount)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        position.allocated = position.allocated.checked_add(token_amount)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        position.fees = position.fees.checked_add(fee)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        if let Some((broker, sales)) = referral {
+            let referred = position.referrals.get(broker.clone())
+                .unwrap_or(BrokerSales { volume: 0, tokens_sold: 0, accrued: 0, claimed: 0 });
+            position.referrals.set(broker.clone(), BrokerSales {
+                volume: referred.volume + sales.volume,
+                tokens_sold: referred.tokens_sold + sales.tokens_sold,
+                accrued: referred.accrued + sales.accrued,
+                claimed: 0,
+            });
+        }
+        env.storage().persistent().set(&position_key, &position);
+        Self::extend_persistent_ttl(env, &position_key);
+
+        // Lock the allocated tokens against transfer until the offering settles
+        let allocation_key = DataKey::OfferingAllocation(offering.id, beneficiary.clone());
+        let allocation: i128 = env.storage().persistent().get(&allocation_key).unwrap_or(0);
+        let allocation = allocation.checked_add(token_amount)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        env.storage().persistent().set(&allocation_key, &allocation);
+        Self::extend_persistent_ttl(env, &allocation_key);
+
+        // Proceeds stay in escrow until the soft cap is met
+        Ok(offering.raised < offering.soft_cap)
+    }
+
+    // Helper to enforce investment limits and track cumulative USDC invested per beneficiary
+    fn record_investment(env: &Env, beneficiary: &Address, usdc_amount: i128) -> Result<(), Error> {
+        let total_key = DataKey::TotalInvested(beneficiary.clone());
+        let total = Self::total_invested(env.clone(), beneficiary.clone()).checked_add(usdc_amount)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+
+        let class = Self::investor_class(env.clone(), beneficiary.clone());
+        if let Some(limits) = Self::investment_limits(env.clone(), class) {
+            if usdc_amount < limits.min_purchase {
+                return Err(Error::from_contract_error(ERR_BELOW_MIN_INVESTMENT));
+            }
+            if limits.max_purchase > 0 && usdc_amount > limits.max_purchase {
+                return Err(Error::from_contract_error(ERR_ABOVE_MAX_INVESTMENT));
+            }
+            if limits.max_total > 0 && total > limits.max_total {
+                return Err(Error::from_contract_error(ERR_INVESTOR_CAP_EXCEEDED));
+            }
+        }
+
+        env.storage().persistent().set(&total_key, &total);
+        Self::extend_persistent_ttl(env, &total_key);
+
+        Ok(())
+    }
+
+    // Helper to check an offering has closed and no escrowed USDC remains to be refunded
+    fn require_offering_settled(env: &Env, offering: &Offering) -> Result<(), Error> {
+        let ended = env.ledger().timestamp() >= offering.end;
+        let escrow_outstanding = offering.raised > 0 && offering.raised < offering.soft_cap;
+        if !ended || escrow_outstanding {
+            return Err(Error::from_contract_error(ERR_OFFERING_NOT_SETTLED));
+        }
+        Ok(())
+    }
+
+    // Helper to refund a failed-offering position to its buyer and reclaim the beneficiary's tokens
+    fn refund_position(env: &Env, buyer: &Address, beneficiary: &Address) -> Result<(), Error> {
+        // Refunds are only available once the offering closed below its soft cap
+        let mut offering = Self::offering(env.clone())
+            .ok_or(Error::from_contract_error(ERR_REFUND_NOT_AVAILABLE))?;
+        if env.ledger().timestamp() < offering.end || offering.raised >= offering.soft_cap {
+            return Err(Error::from_contract_error(ERR_REFUND_NOT_AVAILABLE));
+        }
+
+        let position_key = DataKey::OfferingPosition(offering.id, buyer.clone(), beneficiary.clone());
+        let position = Self::offering_position(env.clone(), buyer.clone(), beneficiary.clone());
+        if position.contributed == 0 && position.allocated == 0 {
+            return Err(Error::from_contract_error(ERR_NOTHING_TO_REFUND));
+        }
+
+        let metadata = Self::get_metadata(env);
+
+        // Reclaim the locked tokens from the beneficiary; a clawback may already have returned some
+        let reclaimed = position.allocated.min(Self::default_balance(env, beneficiary)).max(0);
+        if reclaimed > 0 {
+            let beneficiary_balance = Self::balance(env.clone(), beneficiary.clone());
+            let issuer_balance = Self::balance(env.clone(), metadata.issuer.clone());
+            let new_issuer_balance = issuer_balance.checked_add(reclaimed)
+                .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+
+            Self::write_balance(env, beneficiary, beneficiary_balance - reclaimed)?;
+            Self::write_balance(env, &metadata.issuer, new_issuer_balance)?;
+            Self::emit_transfer(env, beneficiary, &metadata.issuer, reclaimed);
+        }
+
+        // Release the beneficiary's lock for this position
+        let allocation_key = DataKey::OfferingAllocation(offering.id, beneficiary.clone());
+        let allocation: i128 = env.storage().persistent().get(&allocation_key).unwrap_or(0);
+        env.storage().persistent().set(&allocation_key, &(allocation - position.allocated).max(0));
+        Self::extend_persistent_ttl(env, &allocation_key);
+
+        // Refund escrowed USDC to the buyer
+        if position.contributed > 0 {
+            Self::transfer_out(env, &metadata.usdc_token, buyer, position.contributed)?;
+
+            // Reverse escrowed broker commissions
+            let mut commissions: i128 = 0;
+            for (broker, referred) in position.referrals.iter() {
+                let sales_key = DataKey::BrokerSales(broker.clone(), metadata.usdc_token.clone());
+                let mut sales = Self::broker_sales(env.clone(), broker.clone(), metadata.usdc_token.clone());
+                sales.volume = sales.volume.checked_sub(referred.volume)
+                    .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+                sales.tokens_sold = sales.tokens_sold.checked_sub(referred.tokens_sold)
+                    .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+                sales.accrued = sales.accrued.checked_sub(referred.accrued)
+                    .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+                env.storage().persistent().set(&sales_key, &sales);
+
+                let escrow_key = DataKey::EscrowedCommission(offering.id, broker);
+                let escrowed: i128 = env.storage().persistent().get(&escrow_key).unwrap_or(0);
+                let escrowed = escrowed.checked_sub(referred.accrued)
+                    .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+                env.storage().persistent().set(&escrow_key, &escrowed);
+
+                commissions = commissions.checked_add(referred.accrued)
+                    .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+            }
+            Self::adjust_unclaimed_commissions(env, &metadata.usdc_token, -commissions);
+            offering.commissions = offering.commissions.checked_sub(commissions)
+                .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+
+            // Reverse the issuer's proceeds and the escrowed platform fee
+            let new_usdc_balance = Self::usdc_balance(env.clone())
+                .checked_sub(position.contributed - position.fees - commissions)
+                .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+            Self::set_asset_balance(env, &metadata.usdc_token, new_usdc_balance);
+
+            // Escrowed fees belong to the current recipient, which cannot change while they are held
+            if position.fees > 0 {
+                let config = Self::fee_config(env.clone())
+                    .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+                let new_accrued_fees = Self::accrued_fees(env.clone(), config.recipient.clone(), metadata.usdc_token.clone())
+                    .checked_sub(position.fees)
+                    .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+                Self::set_accrued_fees(env, &config.recipient, &metadata.usdc_token, new_accrued_fees);
+            }
+
+            // Refunded USDC no longer counts against the beneficiary's cumulative cap
+            let total_key = DataKey::TotalInvested(beneficiary.clone());
+            let total_invested = Self::total_invested(env.clone(), beneficiary.clone())
+                .checked_sub(position.contributed)
+                .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+            env.storage().persistent().set(&total_key, &total_invested.max(0));
+            Self::extend_persistent_ttl(env, &total_key);
+
+            offering.raised = offering.raised.checked_sub(position.contributed)
+                .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+            offering.fees = offering.fees.checked_sub(position.fees)
+                .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+            env.storage().instance().set(&OFFERING_KEY, &offering);
+        }
+
+        env.storage().persistent().remove(&position_key);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(env);
+
+        // Emit event
+        Self::publish_event(
+            env,
+            (symbol_short!("refund"), buyer.clone(), beneficiary.clone()),
+            SecurityTokenEvent::RefundClaimed(buyer.clone(), position.contributed, reclaimed),
+        );
+
+        Ok(())
+    }
+
+    // Helper to pay out tracked issuer proceeds to allowlisted destinations
+    fn withdraw_proceeds(
+        env: &Env,
+        caller: &Address,
+        asset: &Address,
+        payouts: &Vec<(Address, i128)>,
+        queued: bool,
+    ) -> Result<(), Error> {
+        // Check if caller is issuer (only issuer can withdraw proceeds)
+        if !Self::is_issuer(env, caller) {
+            return Err(Error::from_contract_error(ERR_NOT_ISSUER));
+        }
+
+        // Validate destinations and total amount
+        let mut amount: i128 = 0;
+        for (destination, payout) in payouts.iter() {
+            if !Self::is_withdrawal_destination(env.clone(), destination) {
+                return Err(Error::from_contract_error(ERR_DESTINATION_NOT_ALLOWED));
+            }
+            if payout <= 0 {
+                return Err(Error::from_contract_error(ERR_INVALID_WITHDRAW_AMOUNT));
+            }
+            amount = amount.checked_add(payout)
+                .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        }
+
+        // Get tracked balance using helper
+        let asset_balance = Self::asset_balance(env.clone(), asset.clone());
+
+        // Validate amount
+        if amount <= 0 || amount > asset_balance {
+            return Err(Error::from_contract_error(ERR_INVALID_WITHDRAW_AMOUNT));
+        }
+
+        // USDC raised below the offering soft cap stays in escrow
+        let metadata = Self::get_metadata(env);
+        let escrowed_proceeds = Self::escrowed_usdc(env.clone())
+            - Self::escrowed_fees(env)
+            - Self::escrowed_commissions(env);
+        if asset == &metadata.usdc_token && amount > asset_balance - escrowed_proceeds {
+            return Err(Error::from_contract_error(ERR_USDC_IN_ESCROW));
+        }
+
+        // Enforce the USDC withdrawal policy
+        if asset == &metadata.usdc_token {
+            Self::record_usdc_withdrawal(env, amount, queued)?;
+        }
+
+        // Transfer from contract to each destination with balance verification
+        for (destination, payout) in payouts.iter() {
+            Self::transfer_out(env, asset, &destination, payout)?;
+        }
+
+        // Update tracked balance
+        let new_asset_balance = asset_balance.checked_sub(amount)
+            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+        Self::set_asset_balance(env, asset, new_asset_balance);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(env);
+
+        Ok(())
+    }
+
+    // Helper to enforce the timelock threshold and rolling-window cap on USDC withdrawals
+    fn record_usdc_withdrawal(env: &Env, amount: i128, queued: bool) -> Result<(), Error> {
+        let policy = match Self::withdrawal_policy(env.clone()) {
+            Some(policy) => policy,
+            None => return Ok(()),
+        };
+
+        // Large withdrawals must go through the queue
+        if !queued && policy.timelock_threshold > 0 && amount > policy.timelock_threshold {
+            return Err(Error::from_contract_error(ERR_WITHDRAWAL_REQUIRES_QUEUE));
+        }
+
+        if policy.period_cap == 0 {
+            return Ok(());
+        }
+
+        // History is kept as (first ledger, last ledger, amount) buckets. A bucket counts
+        // until its last withdrawal leaves the rolling window, so the sum never undercounts.
+        let current_ledger = env.ledger().sequence();
+        let bucket_ledgers = (policy.period_ledgers / WITHDRAWAL_BUCKETS).max(1);
+        let history: Vec<(u32, u32, i128)> = env
+            .storage()
+            .instance()
+            .get(&WD_HIST_KEY)
+            .unwrap_or(Vec::new(env));
+        let mut recent = Vec::new(env);
+        let mut withdrawn = amount;
+        for (first, last, withdrawal) in history.iter() {
+            if current_ledger.saturating_sub(last) < policy.period_ledgers {
+                recent.push_back((first, last, withdrawal));
+                withdrawn = withdrawn.checked_add(withdrawal)
+                    .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+            }
+        }
+
+        if withdrawn > policy.period_cap {
+            return Err(Error::from_contract_error(ERR_WITHDRAWAL_CAP_EXCEEDED));
+        }
+
+        // Add to the newest bucket while it is still open, otherwise start a new one
+        match recent.last() {
+            Some((first, _, withdrawal)) if current_ledger.saturating_sub(first) < bucket_ledgers => {
+                recent.set(recent.len() - 1, (first, current_ledger, withdrawal + amount));
+            }
+            _ => recent.push_back((current_ledger, current_ledger, amount)),
+        }
+        env.storage().instance().set(&WD_HIST_KEY, &recent);
+
+        Ok(())
+    }
+
+    // Helper to check if a new withdrawal policy allows more than the current one
+    fn loosens_policy(current: &WithdrawalPolicy, policy: &WithdrawalPolicy) -> bool {
+        let cap_loosened = current.period_cap > 0
+            && (policy.period_cap == 0
+                || policy.period_cap > current.period_cap
+                || policy.period_ledgers < current.period_ledgers);
+        let timelock_loosened = current.timelock_threshold > 0
+            && (policy.timelock_threshold == 0
+                || policy.timelock_threshold > current.timelock_threshold
+                || policy.timelock_ledgers < current.timelock_ledgers);
+        cap_loosened || timelock_loosened
+    }
+
+    // Helper to check if address is the guardian
+    fn is_guardian(env: &Env, address: &Address) -> bool {
+        match Self::guardian(env.clone()) {
+            Some(guardian) => &guardian == address,
+            None => false,
+        }
+    }
+
+    // Helper to emit an event per USDC withdrawal payout
+    fn emit_withdrawals(env: &Env, caller: &Address, payouts: &Vec<(Address, i128)>) {
+        for (destination, payout) in payouts.iter() {
+            Self::publish_event(
+                env,
+                (symbol_short!("withdrawt"), caller.clone(), destination.clone()),
+                SecurityTokenEvent::UsdcWithdrawnTo(caller.clone(), destination, payout),
+            );
+        }
+    }
+
+    // Helper to calculate the payment amount for a token amount at a given price
+    fn calculate_cost(metadata: &TokenMetadata, token_amount: i128, price: i128) -> Result<i128, Error> {
+        let decimals_pow = DECIMAL_BASE.checked_pow(metadata.decimals)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+
+        let cost = token_amount.checked_mul(price)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+            .checked_div(decimals_pow)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+
+        if cost <= 0 {
+            return Err(Error::from_contract_error(ERR_CALCULATION_OVERFLOW));
+        }
+
+        Ok(cost)
+    }
+
+    // Helper to sell issuer tokens to a beneficiary for a payment asset, returning the amount paid
+    fn execute_purchase(
+        env: &Env,
+        metadata: &TokenMetadata,
+        buyer: &Address,
+        beneficiary: &Address,
+        token_amount: i128,
+        asset: &Address,
+        price: i128,
+        max_payment: Option<i128>,
+    ) -> Result<i128, Error> {
+        // Validate amount
+        if token_amount <= 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_PURCHASE_AMOUNT));
+        }
+
+        // Purchases stop permanently once the token is wound down
+        Self::require_active(env)?;
+
+        // Check KYC and compliance status for buyer and beneficiary
+        let config = Self::get_config(env);
+        Self::check_compliance_requirements(env, &config, &metadata.issuer, buyer)?;
+        Self::check_compliance_requirements(env, &config, &metadata.issuer, beneficiary)?;
+
+        // Buyer's latest acceptance must match the active terms
+        if let Some(active_terms) = Self::active_terms(env.clone()) {
+            let accepted = Self::terms_acceptance(env.clone(), buyer.clone())
+                .is_some_and(|acceptance| acceptance.document_hash == active_terms);
+            if !accepted {
+                return Err(Error::from_contract_error(ERR_TERMS_NOT_ACCEPTED));
+            }
+        }
+
+        // Calculate payment amount needed
+        let payment_amount = Self::calculate_cost(metadata, token_amount, price)?;
+
+        // Reject if the cost exceeds what the buyer agreed to pay
+        if let Some(max_payment) = max_payment {
+            if payment_amount > max_payment {
+                return Err(Error::from_contract_error(ERR_SLIPPAGE_EXCEEDED));
+            }
+        }
+
+        // Transfer payment from buyer to contract with balance verification
+        Self::transfer_in(env, asset, buyer, payment_amount)?;
+
+        // Get balances using helper functions
+        let issuer_balance = Self::balance(env.clone(), metadata.issuer.clone());
+        let beneficiary_balance = Self::balance(env.clone(), beneficiary.clone());
+
+        // Check if issuer has enough tokens in the default partition
+        if Self::default_balance(env, &metadata.issuer) < token_amount {
+            return Err(Error::from_contract_error(ERR_INSUFFICIENT_ISSUER_TOKENS));
+        }
+
+        // Update token balances in PERSISTENT storage
+        let new_issuer_balance = issuer_balance.checked_sub(token_amount)
+            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+        let new_beneficiary_balance = beneficiary_balance.checked_add(token_amount)
+            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+
+        Self::write_balance(env, &metadata.issuer, new_issuer_balance)?;
+        Self::write_balance(env, beneficiary, new_beneficiary_balance)?;
+        Self::emit_transfer(env, &metadata.issuer, beneficiary, token_amount);
+
+        Ok(payment_amount)
+    }
+
+    // Helper to calculate the platform fee on a payment amount
+    fn calculate_fee(env: &Env, payment_amount: i128) -> Result<i128, Error> {
+        let fee_bps = match Self::fee_config(env.clone()) {
+            Some(config) => config.fee_bps as i128,
+            None => return Ok(0),
+        };
+        payment_amount.checked_mul(fee_bps)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+            .checked_div(BPS_DENOMINATOR)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))
+    }
+
+    // Helper to credit purchase proceeds to the issuer and settle the platform fee.
+    // Fees on escrowed offering contributions are always accrued so refunds can reverse them.
+    fn credit_proceeds(
+        env: &Env,
+        asset: &Address,
+        payment_amount: i128,
+        fee: i128,
+        referral: &Option<(Address, BrokerSales)>,
+        escrowed: bool,
+    ) -> Result<(), Error> {
+        // Accrue the referring broker's commission
+        let mut commission = 0;
+        if let Some((broker, sales)) = referral {
+            commission = sales.accrued;
+            Self::record_referral(env, broker, asset, sales, escrowed)?;
+        }
+
+        // Update tracked issuer balance
+        let issuer_amount = payment_amount.checked_sub(fee)
+            .and_then(|amount| amount.checked_sub(commission))
+            .filter(|amount| *amount >= 0)
+            .ok_or(Error::from_contract_error(ERR_INVALID_BPS))?;
+        let new_asset_balance = Self::asset_balance(env.clone(), asset.clone())
+            .checked_add(issuer_amount)
+            .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
+        Self::set_asset_balance(env, asset, new_asset_balance);
+
+        if fee <= 0 {
+            return Ok(());
+        }
+
+        let config = Self::fee_config(env.clone())
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        if escrowed || config.mode == FeeMode::Accrued {
+            let new_accrued_fees = Self::accrued_fees(env.clone(), config.recipient.clone(), asset.clone())
+                .checked_add(fee)
+                .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+            Self::set_accrued_fees(env, &config.recipient, asset, new_accrued_fees);
+        } else {
+            Self::transfer_out(env, asset, &config.recipient, fee)?;
+        }
+
+        // Emit fee charged event
+        Self::publish_event(
+            env,
+            (symbol_short!("fee"), config.recipient.clone(), asset.clone()),
+            SecurityTokenEvent::FeeCharged(config.recipient, asset.clone(), fee),
+        );
+
+        Ok(())
+    }
+
+    // Helper to validate a referrer and calculate their commission, returned as the sale to record
+    fn calculate_commission(
+        env: &Env,
+        buyer: &Address,
+        referrer: &Option<Address>,
+        token_amount: i128,
+        payment_amount: i128,
+    ) -> Result<Option<(Address, BrokerSales)>, Error> {
+        let referrer = match referrer {
+            Some(referrer) => referrer,
+            None => return Ok(None),
+        };
+
+        if referrer == buyer {
+            return Err(Error::from_contract_error(ERR_SELF_REFERRAL));
+        }
+
+        // Referrer must be an approved broker
+        let broker = Self::broker(env.clone(), referrer.clone())
+            .filter(|broker| broker.approved)
+            .ok_or(Error::from_contract_error(ERR_NOT_APPROVED_BROKER))?;
+
+        let commission = payment_amount.checked_mul(broker.commission_bps as i128)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+            .checked_div(BPS_DENOMINATOR)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+
+        Ok(Some((
+            referrer.clone(),
+            BrokerSales {
+                volume: payment_amount,
+                tokens_sold: token_amount,
+                accrued: commission,
+                claimed: 0,
+            },
+        )))
+    }
+
+    // Helper to add a referred sale to a broker's totals
+    fn record_referral(
+        env: &Env,
+        broker: &Address,
+        asset: &Address,
+        sale: &BrokerSales,
+        escrowed: bool,
+    ) -> Result<(), Error> {
+        let sales_key = DataKey::BrokerSales(broker.clone(), asset.clone());
+        let mut sales = Self::broker_sales(env.clone(), broker.clone(), asset.clone());
+        sales.volume = sales.volume.checked_add(sale.volume)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        sales.tokens_sold = sales.tokens_sold.checked_add(sale.tokens_sold)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        sales.accrued = sales.accrued.checked_add(sale.accrued)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        env.storage().persistent().set(&sales_key, &sales);
+        Self::extend_persistent_ttl(env, &sales_key);
+        Self::adjust_unclaimed_commissions(env, asset, sale.accrued);
+
+        // Commissions on escrowed contributions cannot be claimed until the soft cap is met
+        if escrowed {
+            if let Some(offering) = Self::offering(env.clone()) {
+                let escrow_key = DataKey::EscrowedCommission(offering.id, broker.clone());
+                let escrowed_commission: i128 = env.storage().persistent().get(&escrow_key).unwrap_or(0);
+                env.storage().persistent().set(&escrow_key, &(escrowed_commission + sale.accrued));
+                Self::extend_persistent_ttl(env, &escrow_key);
+            }
+        }
+
+        // Emit commission accrued event
+        Self::publish_event(
+            env,
+            (symbol_short!("commissn"), broker.clone(), asset.clone()),
+            SecurityTokenEvent::CommissionAccrued(broker.clone(), asset.clone(), sale.accrued),
+        );
+
+        Ok(())
+    }
+
+    // Helper to adjust the total commissions owed to brokers in a payment asset
+    fn adjust_unclaimed_commissions(env: &Env, asset: &Address, delta: i128) {
+        let unclaimed_key = DataKey::UnclaimedCommissions(asset.clone());
+        let unclaimed = Self::unclaimed_commissions(env.clone(), asset.clone());
+        env.storage().persistent().set(&unclaimed_key, &(unclaimed + delta));
+        Self::extend_persistent_ttl(env, &unclaimed_key);
+    }
+
+    // Helper to compare tracked liabilities in an asset with the contract's actual balance
+    fn reconcile_asset(env: &Env, asset: &Address) -> Reconciliation {
+        let mut tracked = Self::asset_balance(env.clone(), asset.clone())
+            + Self::total_accrued_fees(env.clone(), asset.clone())
+            + Self::unclaimed_commissions(env.clone(), asset.clone());
+        if asset == &Self::get_metadata(env).usdc_token {
+            if let Some(wind_down) = Self::wind_down(env.clone()) {
+                tracked += wind_down.proceeds - wind_down.paid;
+            }
+            if let Some(coupon) = Self::coupon_state(env.clone()) {
+                tracked += coupon.funded - coupon.claimed;
+            }
+        }
+        let actual = token::Client::new(env, asset).balance(&env.current_contract_address());
+        Reconciliation {
+            tracked,
+            actual,
+            surplus: actual - tracked,
+        }
+    }
+
+    // Helper to get the broker commissions held in offering escrow
+    fn escrowed_commissions(env: &Env) -> i128 {
+        match Self::offering(env.clone()) {
+            Some(offering) if offering.raised < offering.soft_cap => offering.commissions,
+            _ => 0,
+        }
+    }
+
+    // Helper to build an empty offering position
+    fn empty_position(env: &Env) -> OfferingPosition {
+        OfferingPosition {
+            contributed: 0,
+            allocated: 0,
+            fees: 0,
+            referrals: Map::new(env),
+        }
+    }
+
+    // Helper to set a recipient's accrued platform fees for a payment asset, keeping the total in sync
+    fn set_accrued_fees(env: &Env, recipient: &Address, asset: &Address, amount: i128) {
+        let previous = Self::accrued_fees(env.clone(), recipient.clone(), asset.clone());
+        let fees_key = DataKey::AccruedFees(recipient.clone(), asset.clone());
+        env.storage().persistent().set(&fees_key, &amount);
+        Self::extend_persistent_ttl(env, &fees_key);
+
+        let total_key = DataKey::TotalAccruedFees(asset.clone());
+        let total = Self::total_accrued_fees(env.clone(), asset.clone());
+        env.storage().persistent().set(&total_key, &(total + amount - previous));
+        Self::extend_persistent_ttl(env, &total_key);
+    }
+
+    // Helper to get the highest commission rate among approved brokers
+    fn max_commission_bps(env: &Env) -> u32 {
+        let rates: Map<u32, u32> = env.storage().instance().get(&COMM_RATES_KEY).unwrap_or(Map::new(env));
+        rates.keys().last().unwrap_or(0)
+    }
+
+    // Helper to count approved brokers per commission rate
+    fn adjust_commission_rate(env: &Env, commission_bps: u32, delta: i32) {
+        let mut rates: Map<u32, u32> = env.storage().instance().get(&COMM_RATES_KEY).unwrap_or(Map::new(env));
+        let count = rates.get(commission_bps).unwrap_or(0) as i32 + delta;
+        if count > 0 {
+            rates.set(commission_bps, count as u32);
+        } else {
+            rates.remove(commission_bps);
+        }
+        env.storage().instance().set(&COMM_RATES_KEY, &rates);
+    }
+
+    // Helper to get the platform fees held in offering escrow
+    fn escrowed_fees(env: &Env) -> i128 {
+        match Self::offering(env.clone()) {
+            Some(offering) if offering.raised < offering.soft_cap => offering.fees,
+            _ => 0,
+        }
+    }
+
+    // Helper to pull a payment asset from an address into the contract, verifying balances
+    fn transfer_in(env: &Env, asset: &Address, from: &Address, amount: i128) -> Result<(), Error> {
+        let token_client = token::Client::new(env, asset);
+        let contract_address = env.current_contract_address();
+
+        // Verify sender has sufficient balance BEFORE transfer
+        let from_balance_before = token_client.balance(from);
+        if from_balance_before < amount {
+            return Err(Error::from_contract_error(ERR_INSUFFICIENT_USDC_BALANCE));
+        }
+
+        // Get contract's initial balance for verification
+        let contract_balance_before = token_client.balance(&contract_address);
+
+        // Transfer from sender to contract
+        token_client.transfer(from, &contract_address, &amount);
+
+        // Verify the transfer actually occurred by checking balances
+        let from_balance_after = token_client.balance(from);
+        let contract_balance_after = token_client.balance(&contract_address);
+
+        // Verify sender's balance decreased by the expected amount
+        let expected_from_balance = from_balance_before.checked_sub(amount)
+            .ok_or(Error::from_contract_error(ERR_USDC_TRANSFER_VERIFICATION_FAILED))?;
+
+        if from_balance_after != expected_from_balance {
+            return Err(Error::from_contract_error(ERR_USDC_TRANSFER_VERIFICATION_FAILED));
+        }
+
+        // Verify contract's balance increased by the expected amount
+        let expected_contract_balance = contract_balance_before.checked_add(amount)
+            .ok_or(Error::from_contract_error(ERR_USDC_TRANSFER_VERIFICATION_FAILED))?;
+
+        if contract_balance_after != expected_contract_balance {
+            return Err(Error::from_contract_error(ERR_USDC_TRANSFER_VERIFICATION_FAILED));
+        }
+
+        Ok(())
+    }
+
+    // Helper to send a payment asset from the contract to an address, verifying balances
+    fn transfer_out(env: &Env, asset: &Address, to: &Address, amount: i128) -> Result<(), Error> {
+        let token_client = token::Client::new(env, asset);
+        let contract_address = env.current_contract_address();
+
+        // Get initial balances for verification
+        let contract_balance_before = token_client.balance(&contract_address);
+        let to_balance_before = token_client.balance(to);
+
+        // Verify contract has sufficient balance before withdrawal
+        if contract_balance_before < amount {
+            return Err(Error::from_contract_error(ERR_INSUFFICIENT_USDC_IN_CONTRACT));
+        }
+
+        // Transfer from contract to recipient
+        token_client.transfer(&contract_address, to, &amount);
+
+        // Verify the transfer actually occurred by checking balances
+        let contract_balance_after = token_client.balance(&contract_address);
+        let to_balance_after = token_client.balance(to);
+
+        // Verify contract's balance decreased by the expected amount
+        let expected_contract_balance = contract_balance_before.checked_sub(amount)
+            .ok_or(Error::from_contract_error(ERR_USDC_WITHDRAWAL_VERIFICATION_FAILED))?;
+
+        if contract_balance_after != expected_contract_balance {
+            return Err(Error::from_contract_error(ERR_USDC_WITHDRAWAL_VERIFICATION_FAILED));
+        }
+
+        // Verify recipient's balance increased by the expected amount
+        let expected_to_balance = to_balance_before.checked_add(amount)
+            .ok_or(Error::from_contract_error(ERR_USDC_WITHDRAWAL_VERIFICATION_FAILED))?;
+
+        if to_balance_after != expected_to_balance {
+            return Err(Error::from_contract_error(ERR_USDC_WITHDRAWAL_VERIFICATION_FAILED));
         }
 
         Ok(())
     }
 
-    // View function to get token metadata
-    pub fn get_metadata(env: &Env) -> TokenMetadata {
-        env.storage()
-            .instance()
-            .get(&METADATA_KEY)
-            .expect("Token not initialized")
-    }
-
-    // View function to get balance
-    pub fn balance(env: Env, address: Address) -> i128 {
-        env.storage()
-            .persistent()
-            .get(&DataKey::Balance(address))
-            .unwrap_or(INITIAL_BALANCE)
+    // Helper to append an entry to the price history
+    fn record_price_change(env: &Env, updated_by: &Address, previous_price: i128, price: i128) {
+        let index = Self::price_history_len(env.clone());
+        let history_key = DataKey::PriceHistory(index);
+        env.storage().persistent().set(
+            &history_key,
+            &PriceChange {
+                previous_price,
+                price,
+                timestamp: env.ledger().timestamp(),
+                updated_by: updated_by.clone(),
+            },
+        );
+        Self::extend_persistent_ttl(env, &history_key);
+        env.storage().instance().set(&PRICE_CNT_KEY, &(index + 1));
     }
 
-    // View function to check compliance status
-    pub fn check_compliance(env: Env, address: Address) -> ComplianceStatus {
-        env.storage()
-            .persistent()
-            .get(&DataKey::ComplianceStatus(address))
-            .unwrap_or(ComplianceStatus::Pending)
-    }
+    // Helper to write KYC status, enforcing the authorization_revocable rule
+    fn write_kyc_status(
+        env: &Env,
+        config: &ContractConfig,
+        address: &Address,
+        verified: bool,
+    ) -> Result<(), Error> {
+        // Check if authorization is revocable when attempting to revoke
+        if !config.authorization_revocable && !verified {
+            // Get current KYC status
+            let current_kyc: bool = env
+                .storage()
+                .persistent()
+                .get(&DataKey::KycVerified(address.clone()))
+                .unwrap_or(false);
+            
+            // If currently verified and trying to revoke, check if revocation is allowed
+            if current_kyc {
+                return Err(Error::from_contract_error(ERR_AUTHORIZATION_NOT_REVOCABLE));
+            }
+        }
 
-    // View function to check KYC status
-    pub fn is_kyc_verified(env: Env, address: Address) -> bool {
+        // Update KYC status in PERSISTENT storage
+        let kyc_key = DataKey::KycVerified(address.clone());
         env.storage()
             .persistent()
-            .get(&DataKey::KycVerified(address))
-            .unwrap_or(false)
-    }
-
-    // View function to check accumulated USDC balance
-    pub fn usdc_balance(env: Env) -> i128 {
-        env.storage()
-            .instance()
-            .get(&USDC_BAL_KEY)
-            .unwrap_or(INITIAL_BALANCE)
-    }
-
-    // View function to get token price in USDC
-    pub fn token_price(env: Env) -> i128 {
-        let metadata = Self::get_metadata(&env);
-        metadata.usdc_price
-    }
+            .set(&kyc_key, &verified);
 
-    // View function to get the issuer address
-    pub fn get_issuer(env: Env) -> Address {
-        let metadata = Self::get_metadata(&env);
-        metadata.issuer
-    }
+        // Extend TTL for the KYC entry
+        Self::extend_persistent_ttl(env, &kyc_key);
 
-    // Internal helper functions
+        // Emit event
+        Self::publish_event(
+            env,
+            (symbol_short!("kyc"), address.clone()),
+            SecurityTokenEvent::KycVerified(address.clone(), verified),
+        );
 
-    // Helper to extend instance storage TTL
-    fn extend_instance_ttl(env: &Env) {
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND);
+        Ok(())
     }
 
-    // Helper to extend persistent storage TTL for a specific key
-    fn extend_persistent_ttl(env: &Env, key: &DataKey) {
-        if env.storage().persistent().has(key) {
-            env.storage()
+    // Helper to write compliance status, enforcing the authorization_revocable rule
+    fn write_compliance_status(
+        env: &Env,
+        config: &ContractConfig,
+        address: &Address,
+        status: ComplianceStatus,
+    ) -> Result<(), Error> {
+        // Check if authorization is revocable when attempting to downgrade from Approved
+        if !config.authorization_revocable && status != ComplianceStatus::Approved {
+            // Get current compliance status
+            let current_status: ComplianceStatus = env
+                .storage()
                 .persistent()
-                .extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND);
+                .get(&DataKey::ComplianceStatus(address.clone()))
+                .unwrap_or(ComplianceStatus::Pending);
+            
+            // If currently approved and trying to change to non-approved, check if revocation is allowed
+            if current_status == ComplianceStatus::Approved {
+                return Err(Error::from_contract_error(ERR_AUTHORIZATION_NOT_REVOCABLE));
+            }
         }
-    }
 
-    // Helper to get config from storage
-    fn get_config(env: &Env) -> ContractConfig {
+        // Update compliance status in PERSISTENT storage
+        let compliance_key = DataKey::ComplianceStatus(address.clone());
         env.storage()
-            .instance()
-            .get(&CONFIG_KEY)
-            .expect("Contract not initialized")
-    }
+            .persistent()
+            .set(&compliance_key, &status);
 
-    // Helper to check if address is an admin
-    fn is_admin(env: &Env, address: &Address) -> bool {
-        let admins: Vec<Address> = env
-            .storage()
-            .instance()
-            .get(&ADMINS_KEY)
-            .unwrap_or(Vec::new(env));
-        
-        for admin in admins.iter() {
-            if &admin == address {
-                return true;
-            }
-        }
-        false
-    }
+        // Extend TTL for the compliance entry
+        Self::extend_persistent_ttl(env, &compliance_key);
 
-    // Helper to check if address is the issuer
-    fn is_issuer(env: &Env, address: &Address) -> bool {
-        let metadata = Self::get_metadata(env);
-        &metadata.issuer == address
+        // Emit event
+        Self::publish_event(
+            env,
+            (symbol_short!("complianc"), address.clone()),
+            SecurityTokenEvent::ComplianceUpdated(address.clone(), status),
+        );
+
+        Ok(())
     }
 
     // Helper to check compliance requirements
@@ -947,9 +5207,6 @@ impl SecurityTokenContract {
             return Err(Error::from_contract_error(ERR_SELF_TRANSFER_NOT_ALLOWED));
         }
 
-        let from_balance_key = DataKey::Balance(from.clone());
-        let to_balance_key = DataKey::Balance(to.clone());
-
         // Get current balances using helper
         let from_balance = Self::balance(env.clone(), from.clone());
         let to_balance = Self::balance(env.clone(), to.clone());
@@ -965,16 +5222,422 @@ impl SecurityTokenContract {
         let new_to_balance = to_balance.checked_add(amount)
             .ok_or(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE))?;
 
+        Self::write_balance(env, from, new_from_balance)?;
+        Self::write_balance(env, to, new_to_balance)?;
+
+        Ok(())
+    }
+
+    // Helper to append an admin action to the audit log, indexed by subject
+    fn record_audit(
+        env: &Env,
+        action: Symbol,
+        caller: &Address,
+        subject: Option<Address>,
+        justification: Option<Justification>,
+    ) {
+        let index = Self::audit_log_len(env.clone());
+        let entry_key = DataKey::AuditEntry(index);
+        env.storage().persistent().set(
+            &entry_key,
+            &AuditEntry {
+                action,
+                caller: caller.clone(),
+                subject: subject.clone(),
+                reason_code: justification.as_ref().map(|j| j.reason_code),
+                document_hash: justification.map(|j| j.document_hash),
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        Self::extend_persistent_ttl(env, &entry_key);
+        env.storage().instance().set(&AUDIT_CNT_KEY, &(index + 1));
+
+        if let Some(subject) = subject {
+            let subject_index = Self::subject_audit_len(env.clone(), subject.clone());
+            let subject_entry_key = DataKey::SubjectAuditEntry(subject.clone(), subject_index);
+            let subject_count_key = DataKey::SubjectAuditCount(subject);
+            env.storage().persistent().set(&subject_entry_key, &index);
+            env.storage().persistent().set(&subject_count_key, &(subject_index + 1));
+            Self::extend_persistent_ttl(env, &subject_entry_key);
+            Self::extend_persistent_ttl(env, &subject_count_key);
+        }
+    }
+
+    // Helper to get the units held outside any named partition
+    fn default_balance(env: &Env, address: &Address) -> i128 {
+        Self::balance(env.clone(), address.clone()) - Self::partitioned_balance(env, address)
+    }
+
+    // Helper to check a holder can move units out of the default partition
+    fn require_unlocked(env: &Env, address: &Address, amount: i128) -> Result<(), Error> {
+        let available = Self::default_balance(env, address);
+        if available < amount {
+            return Err(Error::from_contract_error(ERR_INSUFFICIENT_BALANCE));
+        }
+        if available - Self::locked_allocation(env.clone(), address.clone()) < amount {
+            return Err(Error::from_contract_error(ERR_ALLOCATION_LOCKED));
+        }
+        Ok(())
+    }
+
+    // Helper to get the total held outside the default partition
+    fn partitioned_balance(env: &Env, address: &Address) -> i128 {
+        // After a split, the total is the sum of the individually rounded partitions
+        if Self::split_epoch(env, address) < Self::splits(env.clone()).len() {
+            let mut partitioned = 0;
+            for partition in Self::partitions(env.clone()).iter() {
+                partitioned += Self::balance_of_by_partition(env.clone(), address.clone(), partition);
+            }
+            return partitioned;
+        }
+        env.storage()
+            .persistent()
+            .get(&DataKey::PartitionedBalance(address.clone()))
+            .unwrap_or(INITIAL_BALANCE)
+    }
+
+    // Helper to set a named partition balance and keep the partitioned total in sync
+    fn set_partition_balance(env: &Env, address: &Address, partition: &Symbol, amount: i128) {
+        Self::sync_splits(env, address);
+        let previous = Self::balance_of_by_partition(env.clone(), address.clone(), partition.clone());
+        let balance_key = DataKey::PartitionBalance(address.clone(), partition.clone());
+        env.storage().persistent().set(&balance_key, &amount);
+        Self::extend_persistent_ttl(env, &balance_key);
+
+        let partitioned_key = DataKey::PartitionedBalance(address.clone());
+        let partitioned: i128 = env.storage().persistent().get(&partitioned_key).unwrap_or(INITIAL_BALANCE);
+        env.storage().persistent().set(&partitioned_key, &(partitioned + amount - previous));
+        Self::extend_persistent_ttl(env, &partitioned_key);
+    }
+
+    // Helper to remove units from a holder's named partitions in registration order
+    fn drain_partitions(env: &Env, address: &Address, amount: i128) {
+        let mut remaining = amount;
+        for partition in Self::partitions(env.clone()).iter() {
+            if remaining <= 0 {
+                break;
+            }
+            let balance = Self::balance_of_by_partition(env.clone(), address.clone(), partition.clone());
+            let drained = balance.min(remaining);
+            if drained > 0 {
+                Self::set_partition_balance(env, address, &partition, balance - drained);
+                remaining -= drained;
+            }
+        }
+    }
+
+    // Helper to publish a contract event; data is (schema version, event)
+    fn publish_event<T, D>(env: &Env, topics: T, event: D)
+    where
+        T: Topics,
+        D: IntoVal<Env, Val>,
+    {
+        let event: Val = event.into_val(env);
+        env.events().publish(topics, (EVENT_SCHEMA_VERSION, event));
+    }
+
+    // Helper to emit a SEP-41 transfer event: topics (transfer, from, to), data amount
+    fn emit_transfer(env: &Env, from: &Address, to: &Address, amount: i128) {
+        env.events().publish(
+            (symbol_short!("transfer"), from.clone(), to.clone()),
+            amount,
+        );
+    }
+
+    // Helper to get a holder's coupon checkpoint
+    fn coupon_account(env: Env, holder: Address) -> CouponAccount {
         env.storage()
             .persistent()
-            .set(&from_balance_key, &new_from_balance);
+            .get(&DataKey::CouponAccount(holder))
+            .unwrap_or(CouponAccount { index: 0, accrued: 0 })
+    }
+
+    // Helper to count the coupon payment dates up to a timestamp, stopping at maturity
+    fn coupon_dates_passed(terms: &DebtTerms, timestamp: u64) -> u32 {
+        let last = timestamp.min(terms.maturity);
+        if last < terms.first_payment {
+            return 0;
+        }
+        ((last - terms.first_payment) / terms.period + 1) as u32
+    }
+
+    // Helper to get the coupon state with every passed payment date accrued to the balances
+    // held at that date. The issuer's own inventory earns no coupon.
+    fn due_coupon_state(env: &Env) -> Result<Option<CouponState>, Error> {
+        let (terms, mut coupon) = match (Self::debt_terms(env.clone()), Self::coupon_state(env.clone())) {
+            (Some(terms), Some(coupon)) => (terms, coupon),
+            _ => return Ok(None),
+        };
+        let dates = Self::coupon_dates_passed(&terms, env.ledger().timestamp());
+        if dates <= coupon.payments {
+            return Ok(Some(coupon));
+        }
+
+        // Coupon per token unit for the passed periods, then the total owed to holders
+        let metadata = Self::get_metadata(env);
+        let index_delta = terms.face_value
+            .checked_mul(terms.coupon_bps as i128)
+            .and_then(|value| value.checked_mul(terms.period as i128))
+            .and_then(|value| value.checked_mul(COUPON_INDEX_SCALE))
+            .and_then(|value| value.checked_div(BPS_DENOMINATOR * SECONDS_PER_YEAR * DECIMAL_BASE.pow(metadata.decimals)))
+            .and_then(|value| value.checked_mul((dates - coupon.payments) as i128))
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        let outstanding = metadata.total_supply - Self::balance(env.clone(), metadata.issuer);
+
+        // Round up so the amount owed covers every holder's rounded-down accrual
+        let owed = outstanding
+            .checked_mul(index_delta)
+            .and_then(|value| value.checked_add(COUPON_INDEX_SCALE - 1))
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?
+            / COUPON_INDEX_SCALE;
+
+        coupon.index = coupon.index.checked_add(index_delta)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        coupon.due = coupon.due.checked_add(owed)
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))?;
+        coupon.payments = dates;
+        Ok(Some(coupon))
+    }
+
+    // Helper to store passed payment dates in the coupon state. Runs before any balance
+    // changes so each date accrues to the balances held on it.
+    fn roll_coupon(env: &Env) -> Result<Option<CouponState>, Error> {
+        let coupon = Self::due_coupon_state(env)?;
+        if let Some(coupon) = &coupon {
+            if Self::coupon_state(env.clone()).is_some_and(|stored| stored.payments != coupon.payments) {
+                env.storage().instance().set(&COUPON_KEY, coupon);
+            }
+        }
+        Ok(coupon)
+    }
+
+    // Helper to get the balance a holder earns coupons on; the issuer's inventory earns none
+    fn coupon_bearing_balance(env: &Env, holder: &Address) -> i128 {
+        if Self::is_issuer(env, holder) {
+            return 0;
+        }
+        Self::balance(env.clone(), holder.clone())
+    }
+
+    // Helper to settle a holder's coupon accrual before their balance changes
+    fn settle_coupon(env: &Env, holder: &Address) -> Result<(), Error> {
+        let coupon = match Self::roll_coupon(env)? {
+            Some(coupon) => coupon,
+            None => return Ok(()),
+        };
+        let mut account = Self::coupon_account(env.clone(), holder.clone());
+        if account.index == coupon.index {
+            return Ok(());
+        }
+        let balance = Self::coupon_bearing_balance(env, holder);
+        account.accrued = Self::accrue_coupon(balance, &account, &coupon)?;
+        account.index = coupon.index;
+
+        let account_key = DataKey::CouponAccount(holder.clone());
+        env.storage().persistent().set(&account_key, &account);
+        Self::extend_persistent_ttl(env, &account_key);
+
+        Ok(())
+    }
+
+    // Helper to add the coupon accrued on a balance since the account's checkpoint
+    fn accrue_coupon(balance: i128, account: &CouponAccount, coupon: &CouponState) -> Result<i128, Error> {
+        balance
+            .checked_mul(coupon.index - account.index)
+            .and_then(|value| account.accrued.checked_add(value / COUPON_INDEX_SCALE))
+            .ok_or(Error::from_contract_error(ERR_CALCULATION_OVERFLOW))
+    }
+
+    // Helper to get the number of splits already applied to an account's stored balances
+    fn split_epoch(env: &Env, address: &Address) -> u32 {
         env.storage()
             .persistent()
-            .set(&to_balance_key, &new_to_balance);
+            .get(&DataKey::SplitEpoch(address.clone()))
+            .unwrap_or(0)
+    }
+
+    // Helper to convert a stored amount through the splits an account has not yet applied
+    fn apply_splits(env: &Env, address: &Address, amount: i128) -> i128 {
+        if amount == 0 {
+            return amount;
+        }
+        Self::convert_from_epoch(&Self::splits(env.clone()), Self::split_epoch(env, address), amount)
+    }
+
+    // Helper to convert an amount stored at a split epoch through the later splits, rounding down
+    fn convert_from_epoch(splits: &Vec<StockSplit>, epoch: u32, amount: i128) -> i128 {
+        let mut converted = amount;
+        for split in splits.iter().skip(epoch as usize) {
+            converted = converted * split.numerator as i128 / split.denominator as i128;
+        }
+        converted
+    }
+
+    // Helper to get stored balances awaiting conversion, summed per split epoch
+    fn split_pool(env: &Env) -> Map<u32, i128> {
+        env.storage()
+            .instance()
+            .get(&SPLIT_POOL_KEY)
+            .unwrap_or(Map::new(env))
+    }
+
+    // Helper to rewrite an account's stored balances in post-split units before they change
+    fn sync_splits(env: &Env, address: &Address) {
+        let splits = Self::splits(env.clone());
+        if Self::split_epoch(env, address) >= splits.len() {
+            return;
+        }
+
+        let partitioned = Self::partitioned_balance(env, address);
+        for partition in Self::partitions(env.clone()).iter() {
+            let balance_key = DataKey::PartitionBalance(address.clone(), partition.clone());
+            if env.storage().persistent().has(&balance_key) {
+                let balance = Self::balance_of_by_partition(env.clone(), address.clone(), partition);
+                env.storage().persistent().set(&balance_key, &balance);
+                Self::extend_persistent_ttl(env, &balance_key);
+            }
+        }
+        let partitioned_key = DataKey::PartitionedBalance(address.clone());
+        if env.storage().persistent().has(&partitioned_key) {
+            env.storage().persistent().set(&partitioned_key, &partitioned);
+            Self::extend_persistent_ttl(env, &partitioned_key);
+        }
+
+        let balance_key = DataKey::Balance(address.clone());
+        let stored: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        if stored > 0 {
+            let balance = Self::balance(env.clone(), address.clone());
+            env.storage().persistent().set(&balance_key, &balance);
+            Self::extend_persistent_ttl(env, &balance_key);
+
+            // Move the balance out of its epoch's pool; the pool's rounding dust is burned
+            let epoch = Self::split_epoch(env, address);
+            let mut pool = Self::split_pool(env);
+            let pooled = pool.get(epoch).unwrap_or(0);
+            let remaining = pooled - stored;
+            if remaining > 0 {
+                pool.set(epoch, remaining);
+            } else {
+                pool.remove(epoch);
+            }
+            env.storage().instance().set(&SPLIT_POOL_KEY, &pool);
+
+            let mut metadata = Self::get_metadata(env);
+            metadata.total_supply = metadata.total_supply
+                - Self::convert_from_epoch(&splits, epoch, pooled)
+                + Self::convert_from_epoch(&splits, epoch, remaining)
+                + balance;
+            env.storage().instance().set(&METADATA_KEY, &metadata);
+        }
+
+        let epoch_key = DataKey::SplitEpoch(address.clone());
+        env.storage().persistent().set(&epoch_key, &splits.len());
+        Self::extend_persistent_ttl(env, &epoch_key);
+    }
+
+    // Helper to emit a SEP-41 burn event: topics (burn, from), data amount
+    fn emit_burn(env: &Env, from: &Address, amount: i128) {
+        env.events().publish((symbol_short!("burn"), from.clone()), amount);
+    }
+
+    // Helper to deposit final proceeds from the caller and enter wind-down
+    fn begin_wind_down(env: &Env, caller: &Address, proceeds: i128) -> Result<(), Error> {
+        Self::require_active(env)?;
+
+        // Validate amount
+        if proceeds <= 0 {
+            return Err(Error::from_contract_error(ERR_INVALID_AMOUNT));
+        }
+
+        // Offering escrow must be settled before the final distribution
+        if let Some(offering) = Self::offering(env.clone()) {
+            Self::require_offering_settled(env, &offering)?;
+        }
+
+        // Transfer proceeds from caller to contract with balance verification
+        let metadata = Self::get_metadata(env);
+        Self::transfer_in(env, &metadata.usdc_token, caller, proceeds)?;
+
+        let wind_down = WindDown {
+            proceeds,
+            supply: metadata.total_supply,
+            redeemed: 0,
+            paid: 0,
+            started_at: env.ledger().timestamp(),
+        };
+        env.storage().instance().set(&WIND_DOWN_KEY, &wind_down);
+
+        // Extend instance TTL
+        Self::extend_instance_ttl(env);
+
+        // Emit event
+        Self::publish_event(
+            env,
+            (symbol_short!("winddown"), caller.clone()),
+            LifecycleEvent::WindDownStarted(caller.clone(), proceeds, wind_down.supply),
+        );
+
+        Ok(())
+    }
+
+    // Helper to reject actions once the token is wound down or migrating to a successor
+    fn require_active(env: &Env) -> Result<(), Error> {
+        if env.storage().instance().has(&WIND_DOWN_KEY) {
+            return Err(Error::from_contract_error(ERR_WOUND_DOWN));
+        }
+        if env.storage().instance().has(&MIGRATION_KEY) {
+            return Err(Error::from_contract_error(ERR_MIGRATING));
+        }
+        Ok(())
+    }
 
-        // Extend TTLs for both sender and receiver balances
-        Self::extend_persistent_ttl(env, &from_balance_key);
-        Self::extend_persistent_ttl(env, &to_balance_key);
+    // Helper to emit a SEP-41 mint event: topics (mint, to), data amount
+    fn emit_mint(env: &Env, to: &Address, amount: i128) {
+        env.events().publish((symbol_short!("mint"), to.clone()), amount);
+    }
+
+    // Helper to write a balance in PERSISTENT storage and keep the holder index in sync
+    fn write_balance(env: &Env, address: &Address, amount: i128) -> Result<(), Error> {
+        Self::settle_coupon(env, address)?;
+        Self::sync_splits(env, address);
+        let balance_key = DataKey::Balance(address.clone());
+        env.storage().persistent().set(&balance_key, &amount);
+        Self::extend_persistent_ttl(env, &balance_key);
+
+        // Membership follows the holder index, since a reverse split can round a balance to zero
+        let count = Self::holder_count(env.clone());
+        let listed = env.storage().persistent().has(&DataKey::HolderPosition(address.clone()));
+        if !listed && amount > 0 {
+            // Append new holder
+            let index_key = DataKey::HolderIndex(count);
+            let position_key = DataKey::HolderPosition(address.clone());
+            env.storage().persistent().set(&index_key, address);
+            env.storage().persistent().set(&position_key, &count);
+            Self::extend_persistent_ttl(env, &index_key);
+            Self::extend_persistent_ttl(env, &position_key);
+            env.storage().instance().set(&HOLDER_CNT_KEY, &(count + 1));
+        } else if listed && amount == 0 {
+            // Swap-remove the holder with the last entry
+            let position_key = DataKey::HolderPosition(address.clone());
+            let position: u32 = env.storage().persistent().get(&position_key).unwrap_or(0);
+            let last = count - 1;
+            if position != last {
+                let last_holder: Address = env
+                    .storage()
+                    .persistent()
+                    .get(&DataKey::HolderIndex(last))
+                    .expect("Holder index out of sync");
+                let index_key = DataKey::HolderIndex(position);
+                let last_position_key = DataKey::HolderPosition(last_holder.clone());
+                env.storage().persistent().set(&index_key, &last_holder);
+                env.storage().persistent().set(&last_position_key, &position);
+                Self::extend_persistent_ttl(env, &index_key);
+                Self::extend_persistent_ttl(env, &last_position_key);
+            }
+            env.storage().persistent().remove(&DataKey::HolderIndex(last));
+            env.storage().persistent().remove(&position_key);
+            env.storage().instance().set(&HOLDER_CNT_KEY, &last);
+        }
 
         Ok(())
     }
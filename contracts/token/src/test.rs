@@ -2,8 +2,8 @@
 use super::*;
 use soroban_sdk::{
     Env,
-    testutils::{Address as SorobanAddress},
-    token,
+    testutils::{Address as SorobanAddress, Events, Ledger},
+    token, vec, IntoVal,
 };
 
 fn create_token_contract<'a>(
@@ -39,6 +39,47 @@ fn create_security_token(
     )
 }
 
+// Minimal SEP-40 price feed used by oracle pricing tests
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_lastprice(env: Env, price: i128, timestamp: u64) {
+        env.storage().instance().set(&symbol_short!("PRICE"), &PriceData { price, timestamp });
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
+
+    pub fn lastprice(env: Env, _asset: OracleAsset) -> Option<PriceData> {
+        env.storage().instance().get(&symbol_short!("PRICE"))
+    }
+}
+
+// Minimal successor token used by migration tests
+#[contract]
+pub struct MockSuccessor;
+
+#[contractimpl]
+impl MockSuccessor {
+    pub fn migrate_mint(env: Env, to: Address, amount: i128, kyc_verified: bool, compliance: ComplianceStatus) {
+        env.storage().persistent().set(&to, &(amount, kyc_verified, compliance));
+    }
+
+    pub fn minted(env: Env, to: Address) -> Option<(i128, bool, ComplianceStatus)> {
+        env.storage().persistent().get(&to)
+    }
+}
+
+// Assert that the last call appended exactly one audit entry for the given action
+fn assert_audited(client: &SecurityTokenContractClient, audited: &mut u32, action: Symbol) {
+    assert_eq!(client.audit_log_len(), *audited + 1);
+    *audited += 1;
+    assert_eq!(client.audit_log(&(*audited - 1), &1).get(0).unwrap().action, action);
+}
+
 #[test]
 fn test_initialize() {
     let env = Env::default();
@@ -98,16 +139,16 @@ fn test_transfer_with_compliance() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set KYC status for users
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_kyc_status(&admin, &user2, &true);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
 
     // Set compliance status for users
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved, &None);
 
-    client.set_transfer_restriction(&admin, &false);
+    client.set_transfer_restriction(&admin, &false, &None);
 
     // Transfer from issuer to user1
     client.transfer(&issuer, &user1, &100_000);
@@ -144,10 +185,10 @@ fn test_clawback() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set KYC and compliance status
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Transfer tokens to user1
     client.transfer(&issuer, &user1, &100_000);
@@ -156,7 +197,7 @@ fn test_clawback() {
     assert_eq!(initial_balance, 100_000);
 
     // Execute clawback
-    client.clawback(&admin, &user1, &25_000);
+    client.clawback(&admin, &user1, &25_000, &None);
 
     // Verify balance after clawback
     let final_balance = client.balance(&user1);
@@ -196,10 +237,10 @@ fn test_purchase_and_withdraw() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set KYC and compliance status
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &buyer, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
 
     // Initial balances
     let initial_buyer_token_balance = client.balance(&buyer);
@@ -211,7 +252,7 @@ fn test_purchase_and_withdraw() {
 
     // Buyer purchases 500,000 tokens for 50,000,000 (0.01 USDC per token)
     let purchase_amount = 500_000_000;
-    client.purchase(&buyer, &buyer, &purchase_amount);
+    client.purchase(&buyer, &buyer, &purchase_amount, &None);
 
     // Check token balances after purchase
     let buyer_token_balance = client.balance(&buyer);
@@ -225,7 +266,7 @@ fn test_purchase_and_withdraw() {
     assert_eq!(contract_usdc_balance, 50_000_000);
 
     // Issuer withdraws USDC
-    client.withdraw_usdc(&issuer, &30_000_000);
+    client.withdraw_usdc(&issuer, &30_000_000, &None);
 
     // Check balances after withdrawal
     let issuer_usdc_balance = usdc_token_client.balance(&issuer);
@@ -265,15 +306,15 @@ fn test_purchase_insufficient_usdc_balance() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set KYC and compliance status for issuer and buyer
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &buyer, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
 
     // Attempt purchase that exceeds buyer's available USDC balance.
     // For example, if buyer can only afford 100 tokens, purchasing 200 tokens should fail.
     let purchase_amount = 200_000_000;
-    client.purchase(&buyer, &buyer, &purchase_amount);
+    client.purchase(&buyer, &buyer, &purchase_amount, &None);
 }
 
 #[test]
@@ -295,9 +336,9 @@ fn test_transfer_without_kyc() {
     env.mock_all_auths();
 
     // Set KYC only for issuer (user1 remains unset)
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Attempt transfer from issuer to user1 should fail due to missing KYC for user1.
     client.transfer(&issuer, &user1, &100_000);
@@ -322,13 +363,13 @@ fn test_transfer_restricted_fail() {
     env.mock_all_auths();
 
     // Set KYC and compliance for issuer and user1
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Explicitly enable transfer restrictions (token initializes with this true by default)
-    client.set_transfer_restriction(&admin, &true);
+    client.set_transfer_restriction(&admin, &true, &None);
 
     // Transfer tokens from issuer (an admin) to user1 so that user1 has tokens.
     client.transfer(&issuer, &user1, &100_000);
@@ -357,16 +398,16 @@ fn test_clawback_exceeds_balance() {
     env.mock_all_auths();
 
     // Set KYC and compliance for issuer and user1
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Transfer a small amount to user1
     client.transfer(&issuer, &user1, &50_000);
 
     // Attempt to clawback more tokens than user1 holds should fail.
-    client.clawback(&admin, &user1, &100_000);
+    client.clawback(&admin, &user1, &100_000, &None);
 }
 
 // ===== Additional Test Coverage =====
@@ -389,11 +430,11 @@ fn test_add_admin() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Add new admin (only issuer can add admins)
-    client.add_admin(&issuer, &new_admin);
+    client.add_admin(&issuer, &new_admin, &None);
 
     // Verify new admin can perform admin functions
-    client.set_kyc_status(&new_admin, &issuer, &true);
-    client.set_compliance_status(&new_admin, &issuer, &ComplianceStatus::Approved);
+    client.set_kyc_status(&new_admin, &issuer, &true, &None);
+    client.set_compliance_status(&new_admin, &issuer, &ComplianceStatus::Approved, &None);
 }
 
 #[test]
@@ -415,7 +456,7 @@ fn test_add_admin_unauthorized() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Non-issuer admin tries to add admin should fail
-    client.add_admin(&admin, &new_admin);
+    client.add_admin(&admin, &new_admin, &None);
 }
 
 #[test]
@@ -436,7 +477,7 @@ fn test_add_admin_duplicate() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Try to add admin again should fail (issuer trying to add existing admin)
-    client.add_admin(&issuer, &admin);
+    client.add_admin(&issuer, &admin, &None);
 }
 
 #[test]
@@ -456,10 +497,10 @@ fn test_remove_admin() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Add another admin
-    client.add_admin(&issuer, &another_admin);
+    client.add_admin(&issuer, &another_admin, &None);
 
     // Remove the admin
-    client.remove_admin(&issuer, &admin);
+    client.remove_admin(&issuer, &admin, &None);
 
     // Verify issuer is still admin
     assert_eq!(client.get_issuer(), issuer);
@@ -483,10 +524,10 @@ fn test_remove_admin_unauthorized() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Add another admin
-    client.add_admin(&issuer, &another_admin);
+    client.add_admin(&issuer, &another_admin, &None);
 
     // Non-issuer admin tries to remove another admin - should fail
-    client.remove_admin(&admin, &another_admin);
+    client.remove_admin(&admin, &another_admin, &None);
 }
 
 #[test]
@@ -506,7 +547,7 @@ fn test_remove_admin_issuer_protected() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Try to remove issuer - should fail
-    client.remove_admin(&issuer, &issuer);
+    client.remove_admin(&issuer, &issuer, &None);
 }
 
 #[test]
@@ -527,7 +568,7 @@ fn test_remove_admin_not_an_admin() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Try to remove non-admin - should fail
-    client.remove_admin(&issuer, &non_admin);
+    client.remove_admin(&issuer, &non_admin, &None);
 }
 
 #[test]
@@ -546,12 +587,12 @@ fn test_remove_last_admin() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Remove the admin (only issuer remains)
-    client.remove_admin(&issuer, &admin);
+    client.remove_admin(&issuer, &admin, &None);
 
     // Verify issuer is still admin and can perform admin functions
     assert_eq!(client.get_issuer(), issuer);
     let new_admin = Address::generate(&env);
-    client.add_admin(&issuer, &new_admin);
+    client.add_admin(&issuer, &new_admin, &None);
 }
 
 #[test]
@@ -573,25 +614,25 @@ fn test_configure_authorization() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up users with KYC and compliance
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_kyc_status(&admin, &user2, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved, &None);
 
     // Disable transfer restrictions first
-    client.set_transfer_restriction(&admin, &false);
+    client.set_transfer_restriction(&admin, &false, &None);
 
     // Configure authorization to not require it
-    client.configure_authorization(&admin, &false, &false);
+    client.configure_authorization(&admin, &false, &false, &None);
 
     // Transfer should work without KYC/compliance checks
     client.transfer(&issuer, &user1, &100_000);
     client.transfer(&user1, &user2, &50_000);
 
     // Re-enable authorization
-    client.configure_authorization(&admin, &true, &true);
+    client.configure_authorization(&admin, &true, &true, &None);
 
     // Transfer should still work since users are already verified
     client.transfer(&user2, &user1, &25_000);
@@ -616,7 +657,7 @@ fn test_configure_authorization_unauthorized() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Non-admin tries to configure authorization should fail
-    client.configure_authorization(&non_admin, &false, &false);
+    client.configure_authorization(&non_admin, &false, &false, &None);
 }
 
 #[test]
@@ -642,11 +683,11 @@ fn test_view_functions() {
     assert_eq!(issuer_compliance, ComplianceStatus::Approved);
 
     // Set compliance status and test
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
     let user1_compliance = client.check_compliance(&user1);
     assert_eq!(user1_compliance, ComplianceStatus::Approved);
 
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Rejected);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Rejected, &None);
     let user1_compliance_rejected = client.check_compliance(&user1);
     assert_eq!(user1_compliance_rejected, ComplianceStatus::Rejected);
 
@@ -655,11 +696,11 @@ fn test_view_functions() {
     let issuer_kyc = client.is_kyc_verified(&issuer);
     assert_eq!(issuer_kyc, true);
 
-    client.set_kyc_status(&admin, &user1, &true);
+    client.set_kyc_status(&admin, &user1, &true, &None);
     let user1_kyc = client.is_kyc_verified(&user1);
     assert_eq!(user1_kyc, true);
 
-    client.set_kyc_status(&admin, &user1, &false);
+    client.set_kyc_status(&admin, &user1, &false, &None);
     let user1_kyc_false = client.is_kyc_verified(&user1);
     assert_eq!(user1_kyc_false, false);
 }
@@ -734,13 +775,13 @@ fn test_transfer_edge_cases() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up user
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Disable transfer restrictions
-    client.set_transfer_restriction(&admin, &false);
+    client.set_transfer_restriction(&admin, &false, &None);
 
     // Transfer to user1
     client.transfer(&issuer, &user1, &100_000);
@@ -774,12 +815,12 @@ fn test_transfer_insufficient_balance() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up users
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_kyc_status(&admin, &user2, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved, &None);
 
     // Transfer small amount to user1
     client.transfer(&issuer, &user1, &50_000);
@@ -807,10 +848,10 @@ fn test_transfer_zero_amount() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up user
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Try to transfer zero amount
     client.transfer(&issuer, &user1, &0);
@@ -848,20 +889,20 @@ fn test_withdraw_usdc_edge_cases() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up buyer
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &buyer, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
 
     // Make a purchase to accumulate USDC
-    client.purchase(&buyer, &buyer, &500_000_000);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
 
     // Test partial withdrawal (issuer only)
-    client.withdraw_usdc(&issuer, &25_000_000);
+    client.withdraw_usdc(&issuer, &25_000_000, &None);
     assert_eq!(client.usdc_balance(), 25_000_000);
 
     // Test full withdrawal (issuer only)
-    client.withdraw_usdc(&issuer, &25_000_000);
+    client.withdraw_usdc(&issuer, &25_000_000, &None);
     assert_eq!(client.usdc_balance(), 0);
 }
 
@@ -884,7 +925,7 @@ fn test_withdraw_usdc_unauthorized() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Non-issuer tries to withdraw USDC (should fail)
-    client.withdraw_usdc(&non_admin, &10_000_000);
+    client.withdraw_usdc(&non_admin, &10_000_000, &None);
 }
 
 #[test]
@@ -920,16 +961,16 @@ fn test_withdraw_usdc_exceeds_balance() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up buyer
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &buyer, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
 
     // Make a purchase to accumulate USDC
-    client.purchase(&buyer, &buyer, &500_000_000);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
 
     // Try to withdraw more than available (issuer only)
-    client.withdraw_usdc(&issuer, &100_000_000);
+    client.withdraw_usdc(&issuer, &100_000_000, &None);
 }
 
 // ===== Additional Tests for 100% Coverage =====
@@ -967,13 +1008,13 @@ fn test_purchase_negative_amount() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up buyer
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &buyer, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
 
     // Try to purchase negative amount
-    client.purchase(&buyer, &buyer, &-100_000);
+    client.purchase(&buyer, &buyer, &-100_000, &None);
 }
 
 #[test]
@@ -1009,13 +1050,13 @@ fn test_purchase_zero_amount() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up buyer
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &buyer, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
 
     // Try to purchase zero amount
-    client.purchase(&buyer, &buyer, &0);
+    client.purchase(&buyer, &buyer, &0, &None);
 }
 
 
@@ -1038,7 +1079,7 @@ fn test_withdraw_usdc_negative_amount() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Try to withdraw negative amount (issuer only)
-    client.withdraw_usdc(&issuer, &-10_000_000);
+    client.withdraw_usdc(&issuer, &-10_000_000, &None);
 }
 
 #[test]
@@ -1059,7 +1100,7 @@ fn test_withdraw_usdc_zero_amount() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Try to withdraw zero amount (issuer only)
-    client.withdraw_usdc(&issuer, &0);
+    client.withdraw_usdc(&issuer, &0, &None);
 }
 
 #[test]
@@ -1081,10 +1122,10 @@ fn test_transfer_negative_amount() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up user
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Try to transfer negative amount
     client.transfer(&issuer, &user1, &-100_000);
@@ -1108,16 +1149,16 @@ fn test_compliance_status_transitions() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Test all compliance status transitions
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Pending);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Pending, &None);
     assert_eq!(client.check_compliance(&user1), ComplianceStatus::Pending);
 
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
     assert_eq!(client.check_compliance(&user1), ComplianceStatus::Approved);
 
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Rejected);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Rejected, &None);
     assert_eq!(client.check_compliance(&user1), ComplianceStatus::Rejected);
 
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Suspended);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Suspended, &None);
     assert_eq!(client.check_compliance(&user1), ComplianceStatus::Suspended);
 }
 
@@ -1141,10 +1182,10 @@ fn test_kyc_status_transitions() {
     // Test KYC status transitions
     assert_eq!(client.is_kyc_verified(&user1), false);
 
-    client.set_kyc_status(&admin, &user1, &true);
+    client.set_kyc_status(&admin, &user1, &true, &None);
     assert_eq!(client.is_kyc_verified(&user1), true);
 
-    client.set_kyc_status(&admin, &user1, &false);
+    client.set_kyc_status(&admin, &user1, &false, &None);
     assert_eq!(client.is_kyc_verified(&user1), false);
 }
 
@@ -1166,21 +1207,21 @@ fn test_transfer_restriction_toggle() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up user
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Test transfer restriction toggle
     // Initially restricted (default)
     client.transfer(&issuer, &user1, &100_000); // Admin can transfer
 
     // Disable restrictions
-    client.set_transfer_restriction(&admin, &false);
+    client.set_transfer_restriction(&admin, &false, &None);
     client.transfer(&user1, &issuer, &50_000); // User can transfer
 
     // Re-enable restrictions
-    client.set_transfer_restriction(&admin, &true);
+    client.set_transfer_restriction(&admin, &true, &None);
     client.transfer(&issuer, &user1, &50_000); // Admin can transfer
 }
 
@@ -1234,11 +1275,11 @@ fn test_balance_queries() {
     assert_eq!(client.balance(&user1), 0);
 
     // Transfer and check balance
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
-    client.set_transfer_restriction(&admin, &false);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
 
     client.transfer(&issuer, &user1, &100_000);
     assert_eq!(client.balance(&user1), 100_000);
@@ -1277,23 +1318,23 @@ fn test_usdc_balance_tracking() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set up buyer
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &buyer, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
 
     // Test USDC balance tracking
     assert_eq!(client.usdc_balance(), 0);
 
     // Make purchases and track balance
-    client.purchase(&buyer, &buyer, &500_000_000);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
     assert_eq!(client.usdc_balance(), 50_000_000);
 
-    client.purchase(&buyer, &buyer, &300_000_000);
+    client.purchase(&buyer, &buyer, &300_000_000, &None);
     assert_eq!(client.usdc_balance(), 80_000_000);
 
     // Withdraw and check balance (issuer only)
-    client.withdraw_usdc(&issuer, &30_000_000);
+    client.withdraw_usdc(&issuer, &30_000_000, &None);
     assert_eq!(client.usdc_balance(), 50_000_000);
 }
 
@@ -1350,12 +1391,12 @@ fn test_purchase_with_different_beneficiary() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set KYC and compliance status for all parties
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &buyer, &true);
-    client.set_kyc_status(&admin, &beneficiary, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &beneficiary, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_kyc_status(&admin, &beneficiary, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &beneficiary, &ComplianceStatus::Approved, &None);
 
     // Initial balances
     let initial_buyer_token_balance = client.balance(&buyer);
@@ -1369,7 +1410,7 @@ fn test_purchase_with_different_beneficiary() {
 
     // Buyer purchases 500,000 tokens for beneficiary (50,000,000 USDC at 0.1 USDC per token)
     let purchase_amount = 500_000_000;
-    client.purchase(&buyer, &beneficiary, &purchase_amount);
+    client.purchase(&buyer, &beneficiary, &purchase_amount, &None);
 
     // Check token balances after purchase
     // Buyer should have 0 tokens (didn't receive any)
@@ -1404,10 +1445,10 @@ fn test_clawback_partial_amount() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set KYC and compliance for issuer and user1
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Transfer tokens to user1
     client.transfer(&issuer, &user1, &100_000);
@@ -1419,7 +1460,7 @@ fn test_clawback_partial_amount() {
     let issuer_balance_before = client.balance(&issuer);
 
     // Clawback partial amount (less than user holds)
-    client.clawback(&admin, &user1, &30_000);
+    client.clawback(&admin, &user1, &30_000, &None);
 
     // Verify partial clawback worked
     let user_balance_after = client.balance(&user1);
@@ -1448,16 +1489,16 @@ fn test_clawback_negative_amount() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set KYC and compliance for issuer and user1
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Transfer tokens to user1
     client.transfer(&issuer, &user1, &100_000);
 
     // Attempt to clawback negative amount - should panic
-    client.clawback(&admin, &user1, &-1);
+    client.clawback(&admin, &user1, &-1, &None);
 }
 
 #[test]
@@ -1478,16 +1519,16 @@ fn test_clawback_zero_amount() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Set KYC and compliance for issuer and user1
-    client.set_kyc_status(&admin, &issuer, &true);
-    client.set_kyc_status(&admin, &user1, &true);
-    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved);
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
 
     // Transfer tokens to user1
     client.transfer(&issuer, &user1, &100_000);
 
     // Attempt to clawback zero amount - should panic
-    client.clawback(&admin, &user1, &0);
+    client.clawback(&admin, &user1, &0, &None);
 }
 
 #[test]
@@ -1736,14 +1777,14 @@ fn test_authorization_revocable_kyc_revoke_blocked() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Grant KYC
-    client.set_kyc_status(&admin, &user1, &true);
+    client.set_kyc_status(&admin, &user1, &true, &None);
     assert_eq!(client.is_kyc_verified(&user1), true);
 
     // Configure authorization to be non-revocable
-    client.configure_authorization(&admin, &true, &false);
+    client.configure_authorization(&admin, &true, &false, &None);
 
     // Attempt to revoke KYC should fail
-    client.set_kyc_status(&admin, &user1, &false);
+    client.set_kyc_status(&admin, &user1, &false, &None);
 }
 
 #[test]
@@ -1765,14 +1806,14 @@ fn test_authorization_revocable_compliance_revoke_blocked() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Grant compliance
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
     assert_eq!(client.check_compliance(&user1), ComplianceStatus::Approved);
 
     // Configure authorization to be non-revocable
-    client.configure_authorization(&admin, &true, &false);
+    client.configure_authorization(&admin, &true, &false, &None);
 
     // Attempt to revoke compliance should fail
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Rejected);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Rejected, &None);
 }
 
 #[test]
@@ -1793,11 +1834,11 @@ fn test_authorization_revocable_kyc_revoke_allowed() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Grant KYC
-    client.set_kyc_status(&admin, &user1, &true);
+    client.set_kyc_status(&admin, &user1, &true, &None);
     assert_eq!(client.is_kyc_verified(&user1), true);
 
     // Revoke KYC should succeed (authorization_revocable = true by default)
-    client.set_kyc_status(&admin, &user1, &false);
+    client.set_kyc_status(&admin, &user1, &false, &None);
     assert_eq!(client.is_kyc_verified(&user1), false);
 }
 
@@ -1819,10 +1860,3367 @@ fn test_authorization_revocable_compliance_revoke_allowed() {
     let client = SecurityTokenContractClient::new(&env, &contract_id);
 
     // Grant compliance
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
     assert_eq!(client.check_compliance(&user1), ComplianceStatus::Approved);
 
     // Revoke compliance should succeed (authorization_revocable = true by default)
-    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Rejected);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Rejected, &None);
     assert_eq!(client.check_compliance(&user1), ComplianceStatus::Rejected);
 }
+
+#[test]
+fn test_set_price_and_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let pricer = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let usdc_token = create_token_contract(&env, &admin);
+    let usdc_token_client = usdc_token.0;
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // Initial price is recorded as the first history entry
+    assert_eq!(client.price_history_len(), 1);
+
+    // Issuer grants the pricing role and the pricer updates the price
+    client.add_pricer(&issuer, &pricer, &None);
+    assert!(client.is_pricer(&pricer));
+    client.set_price(&pricer, &120_000, &None);
+    assert_eq!(client.token_price(), 120_000);
+
+    // Issuer can always update the price
+    client.set_price(&issuer, &110_000, &None);
+    assert_eq!(client.token_price(), 110_000);
+
+    let history = client.price_history(&0, &10);
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(0).unwrap().price, 100_000);
+    assert_eq!(history.get(1).unwrap().previous_price, 100_000);
+    assert_eq!(history.get(1).unwrap().price, 120_000);
+    assert_eq!(history.get(1).unwrap().updated_by, pricer);
+    assert_eq!(history.get(2).unwrap().price, 110_000);
+
+    // Pagination
+    let page = client.price_history(&2, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().price, 110_000);
+
+    // Revoked pricer can no longer update the price
+    client.remove_pricer(&issuer, &pricer, &None);
+    assert!(!client.is_pricer(&pricer));
+    assert!(client.try_set_price(&pricer, &130_000, &None).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn test_set_price_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let usdc_token = create_token_contract(&env, &admin);
+    let usdc_token_client = usdc_token.0;
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // Admin without the pricing role cannot update the price
+    client.set_price(&admin, &120_000, &None);
+}
+
+#[test]
+fn test_set_price_max_change_guard() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let usdc_token = create_token_contract(&env, &admin);
+    let usdc_token_client = usdc_token.0;
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // Limit each update to 10%
+    client.set_max_price_change(&issuer, &1_000, &None);
+    assert_eq!(client.max_price_change(), 1_000);
+
+    // 10% increase is allowed
+    client.set_price(&issuer, &110_000, &None);
+
+    // 50% increase is rejected
+    assert_eq!(
+        client.try_set_price(&issuer, &165_000, &None),
+        Err(Ok(Error::from_contract_error(32)))
+    );
+
+    // Out-of-bounds prices are rejected
+    assert_eq!(
+        client.try_set_price(&issuer, &0, &None),
+        Err(Ok(Error::from_contract_error(31)))
+    );
+    assert_eq!(client.token_price(), 110_000);
+}
+
+#[test]
+fn test_purchase_with_oracle_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // Oracle publishes 0.012 USD per token with 14 decimals
+    env.ledger().set_timestamp(10_000);
+    let oracle_id = env.register(MockOracle, ());
+    let oracle_client = MockOracleClient::new(&env, &oracle_id);
+    oracle_client.set_lastprice(&1_200_000_000_000, &10_000);
+
+    client.set_oracle(&issuer, &OracleConfig {
+        oracle: oracle_id.clone(),
+        asset: OracleAsset::Other(symbol_short!("NAV")),
+        max_age: 3_600,
+        on_stale: StalePriceAction::Halt,
+    }, &None);
+
+    // USDC has 7 decimals, so the effective price is 120_000
+    assert_eq!(client.current_price(), 120_000);
+    assert_eq!(client.token_price(), 100_000);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    client.purchase(&buyer, &buyer, &1_000_000, &None);
+    assert_eq!(client.usdc_balance(), 120_000);
+
+    // Once the feed is older than max_age, purchases halt
+    env.ledger().set_timestamp(20_000);
+    assert_eq!(
+        client.try_purchase(&buyer, &buyer, &1_000_000, &None),
+        Err(Ok(Error::from_contract_error(36)))
+    );
+
+    // Clearing the oracle reverts to the static price
+    client.clear_oracle(&issuer, &None);
+    assert_eq!(client.current_price(), 100_000);
+}
+
+#[test]
+fn test_oracle_stale_price_fallback() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // Oracle has not published a price yet
+    let oracle_id = env.register(MockOracle, ());
+    client.set_oracle(&issuer, &OracleConfig {
+        oracle: oracle_id.clone(),
+        asset: OracleAsset::Stellar(contract_id.clone()),
+        max_age: 3_600,
+        on_stale: StalePriceAction::UseStaticPrice,
+    }, &None);
+
+    // Missing price falls back to the static price
+    assert_eq!(client.current_price(), 100_000);
+
+    // Stale price falls back to the static price
+    env.ledger().set_timestamp(10_000);
+    MockOracleClient::new(&env, &oracle_id).set_lastprice(&1_200_000_000_000, &1_000);
+    assert_eq!(client.current_price(), 100_000);
+
+    // Future-dated price is rejected and falls back to the static price
+    MockOracleClient::new(&env, &oracle_id).set_lastprice(&1_200_000_000_000, &10_001);
+    assert_eq!(client.current_price(), 100_000);
+
+    // Non-pricers cannot configure the oracle
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_clear_oracle(&outsider, &None),
+        Err(Ok(Error::from_contract_error(30)))
+    );
+}
+
+#[test]
+fn test_purchase_with_payment_asset() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    // Setup test USDC and EURC token contracts
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+    let (eurc_token_client, eurc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // Accept EURC at 0.09 EURC per token
+    client.set_payment_asset(&admin, &eurc_token_client.address, &90_000, &7, &None);
+    assert_eq!(client.payment_assets().len(), 1);
+    assert_eq!(
+        client.payment_asset(&eurc_token_client.address),
+        Some(PaymentAsset { price: 90_000, decimals: 7 })
+    );
+
+    eurc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    // Buyer purchases 500 tokens with EURC
+    client.purchase_with_asset(&buyer, &buyer, &500_000_000, &eurc_token_client.address, &None);
+    assert_eq!(client.balance(&buyer), 500_000_000);
+    assert_eq!(eurc_token_client.balance(&buyer), 955_000_000);
+    assert_eq!(client.asset_balance(&eurc_token_client.address), 45_000_000);
+    assert_eq!(client.usdc_balance(), 0);
+
+    // Issuer withdraws EURC proceeds
+    client.withdraw_asset(&issuer, &eurc_token_client.address, &45_000_000, &None);
+    assert_eq!(eurc_token_client.balance(&issuer), 45_000_000);
+    assert_eq!(client.asset_balance(&eurc_token_client.address), 0);
+
+    // Removed assets can no longer be used for purchases
+    client.remove_payment_asset(&admin, &eurc_token_client.address, &None);
+    assert_eq!(
+        client.try_purchase_with_asset(&buyer, &buyer, &1_000_000, &eurc_token_client.address, &None),
+        Err(Ok(Error::from_contract_error(39)))
+    );
+}
+
+#[test]
+fn test_set_payment_asset_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC and EURC token contracts
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+    let (eurc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // USDC cannot be registered as an additional payment asset
+    assert_eq!(
+        client.try_set_payment_asset(&admin, &usdc_token_client.address, &100_000, &7, &None),
+        Err(Ok(Error::from_contract_error(40)))
+    );
+
+    // Decimals must match the asset contract
+    assert_eq!(
+        client.try_set_payment_asset(&admin, &eurc_token_client.address, &90_000, &6, &None),
+        Err(Ok(Error::from_contract_error(40)))
+    );
+
+    // Only admins can manage payment assets
+    assert_eq!(
+        client.try_set_payment_asset(&user1, &eurc_token_client.address, &90_000, &7, &None),
+        Err(Ok(Error::from_contract_error(41)))
+    );
+}
+
+#[test]
+fn test_offering_soft_cap_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    // Offering runs from 1_000 to 2_000 with a 100 USDC soft cap
+    env.ledger().set_timestamp(500);
+    client.configure_offering(&issuer, &1_000, &2_000, &1_000_000_000, &2_000_000_000, &None);
+
+    // Purchases are rejected before the window opens
+    assert_eq!(
+        client.try_purchase(&buyer, &buyer, &500_000_000, &None),
+        Err(Ok(Error::from_contract_error(43)))
+    );
+
+    env.ledger().set_timestamp(1_500);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+    assert_eq!(client.offering().unwrap().raised, 50_000_000);
+    assert_eq!(client.escrowed_usdc(), 50_000_000);
+
+    // Escrowed USDC cannot be withdrawn
+    assert_eq!(
+        client.try_withdraw_usdc(&issuer, &10_000_000, &None),
+        Err(Ok(Error::from_contract_error(46)))
+    );
+
+    // Refunds are not available while the offering is open
+    assert_eq!(
+        client.try_claim_refund(&buyer, &buyer),
+        Err(Ok(Error::from_contract_error(47)))
+    );
+
+    // Offering closes below the soft cap and the buyer is refunded
+    env.ledger().set_timestamp(2_000);
+    client.claim_refund(&buyer, &buyer);
+    assert_eq!(usdc_token_client.balance(&buyer), 1_000_000_000);
+    assert_eq!(client.balance(&buyer), 0);
+    assert_eq!(client.balance(&issuer), 1_000_000_000_000);
+    assert_eq!(client.usdc_balance(), 0);
+    assert_eq!(client.escrowed_usdc(), 0);
+
+    // Nothing left to refund, and the settled offering can be cleared
+    assert_eq!(
+        client.try_claim_refund(&buyer, &buyer),
+        Err(Ok(Error::from_contract_error(48)))
+    );
+    client.clear_offering(&issuer, &None);
+    assert_eq!(client.offering(), None);
+}
+
+#[test]
+fn test_offering_soft_cap_met_and_hard_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    // Soft cap of 50 USDC, hard cap of 80 USDC
+    env.ledger().set_timestamp(1_000);
+    client.configure_offering(&issuer, &1_000, &2_000, &50_000_000, &80_000_000, &None);
+
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+    assert_eq!(client.escrowed_usdc(), 0);
+
+    // Purchases beyond the hard cap are rejected
+    assert_eq!(
+        client.try_purchase(&buyer, &buyer, &400_000_000, &None),
+        Err(Ok(Error::from_contract_error(44)))
+    );
+
+    // A running offering cannot be replaced
+    assert_eq!(
+        client.try_configure_offering(&issuer, &3_000, &4_000, &1, &2, &None),
+        Err(Ok(Error::from_contract_error(45)))
+    );
+
+    // Soft cap met: proceeds are withdrawable and no refunds after close
+    client.withdraw_usdc(&issuer, &50_000_000, &None);
+    env.ledger().set_timestamp(2_000);
+    assert_eq!(
+        client.try_claim_refund(&buyer, &buyer),
+        Err(Ok(Error::from_contract_error(47)))
+    );
+}
+
+#[test]
+fn test_offering_refund_for_other_beneficiary() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+    client.set_kyc_status(&admin, &beneficiary, &true, &None);
+    client.set_compliance_status(&admin, &beneficiary, &ComplianceStatus::Approved, &None);
+
+    env.ledger().set_timestamp(1_000);
+    client.configure_offering(&issuer, &1_000, &2_000, &1_000_000_000, &2_000_000_000, &None);
+    client.purchase(&buyer, &beneficiary, &500_000_000, &None);
+
+    // The position is recorded for the buyer and beneficiary together
+    let position = client.offering_position(&buyer, &beneficiary);
+    assert_eq!(position.contributed, 50_000_000);
+    assert_eq!(position.allocated, 500_000_000);
+    assert_eq!(client.locked_allocation(&beneficiary), 500_000_000);
+
+    // The beneficiary cannot refund a position it did not pay for
+    env.ledger().set_timestamp(2_000);
+    assert_eq!(
+        client.try_claim_refund(&beneficiary, &beneficiary),
+        Err(Ok(Error::from_contract_error(48)))
+    );
+
+    // Refund reclaims the beneficiary's tokens and repays the buyer
+    client.claim_refund(&buyer, &beneficiary);
+    assert_eq!(client.balance(&beneficiary), 0);
+    assert_eq!(usdc_token_client.balance(&buyer), 1_000_000_000);
+    assert_eq!(client.balance(&issuer), 1_000_000_000_000);
+    assert_eq!(client.locked_allocation(&beneficiary), 0);
+    client.clear_offering(&issuer, &None);
+}
+
+#[test]
+fn test_absent_buyer_refunded_by_issuer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+    client.set_kyc_status(&admin, &beneficiary, &true, &None);
+    client.set_compliance_status(&admin, &beneficiary, &ComplianceStatus::Approved, &None);
+
+    env.ledger().set_timestamp(1_000);
+    client.configure_offering(&issuer, &1_000, &2_000, &1_000_000_000, &2_000_000_000, &None);
+    client.purchase(&buyer, &beneficiary, &500_000_000, &None);
+
+    // The offering fails and the buyer never claims, which blocks clearing it
+    env.ledger().set_timestamp(2_000);
+    assert_eq!(
+        client.try_clear_offering(&issuer, &None),
+        Err(Ok(Error::from_contract_error(45)))
+    );
+
+    // The issuer pushes the refund without the buyer's authorization
+    client.refund_investor(&buyer, &beneficiary);
+    assert!(env.auths().is_empty());
+    assert_eq!(usdc_token_client.balance(&buyer), 1_000_000_000);
+    assert_eq!(client.balance(&beneficiary), 0);
+    assert_eq!(client.balance(&issuer), 1_000_000_000_000);
+    assert_eq!(client.escrowed_usdc(), 0);
+
+    // The issuer can move on to a new offering
+    client.clear_offering(&issuer, &None);
+    client.configure_offering(&issuer, &3_000, &4_000, &1_000_000_000, &2_000_000_000, &None);
+    assert_eq!(
+        client.try_refund_investor(&buyer, &beneficiary),
+        Err(Ok(Error::from_contract_error(47)))
+    );
+}
+
+#[test]
+fn test_offering_allocation_locked_until_settled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    // Buyer already holds unlocked tokens from the issuer
+    client.transfer(&issuer, &buyer, &100_000);
+
+    env.ledger().set_timestamp(1_000);
+    client.configure_offering(&issuer, &1_000, &2_000, &1_000_000_000, &2_000_000_000, &None);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+
+    // Allocated tokens cannot leave while the offering can still be refunded
+    client.transfer(&buyer, &user2, &100_000);
+    assert_eq!(
+        client.try_transfer(&buyer, &user2, &1),
+        Err(Ok(Error::from_contract_error(103)))
+    );
+    let class_b = symbol_short!("class_b");
+    client.set_partition(&admin, &class_b, &PartitionRules {
+        locked_until: 0,
+        allowed_classes: vec![&env],
+    }, &None);
+    assert_eq!(
+        client.try_change_partition(&admin, &buyer, &symbol_short!("default"), &class_b, &1, &None),
+        Err(Ok(Error::from_contract_error(103)))
+    );
+
+    // Tokens clawed back before the refund still settle the escrow
+    client.clawback(&admin, &buyer, &200_000_000, &None);
+    env.ledger().set_timestamp(2_000);
+    client.claim_refund(&buyer, &buyer);
+    assert_eq!(client.balance(&buyer), 0);
+    assert_eq!(usdc_token_client.balance(&buyer), 1_000_000_000);
+    assert_eq!(client.balance(&issuer), 1_000_000_000_000 - 100_000);
+    assert_eq!(client.escrowed_usdc(), 0);
+    client.clear_offering(&issuer, &None);
+}
+
+#[test]
+fn test_quote_and_purchase_with_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    // Quote matches the purchase cost
+    let quote = client.quote_purchase(&500_000_000);
+    assert_eq!(quote, 50_000_000);
+    client.purchase_with_limit(&buyer, &buyer, &500_000_000, &quote, &None);
+    assert_eq!(client.usdc_balance(), 50_000_000);
+
+    // Price moves up after the quote; the bounded purchase is rejected
+    client.set_price(&issuer, &120_000, &None);
+    assert_eq!(
+        client.try_purchase_with_limit(&buyer, &buyer, &500_000_000, &quote, &None),
+        Err(Ok(Error::from_contract_error(50)))
+    );
+    assert_eq!(client.balance(&buyer), 500_000_000);
+    assert_eq!(usdc_token_client.balance(&buyer), 950_000_000);
+
+    // Invalid token amounts cannot be quoted
+    assert_eq!(
+        client.try_quote_purchase(&0),
+        Err(Ok(Error::from_contract_error(15)))
+    );
+}
+
+#[test]
+fn test_purchase_for_usdc() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    // 50 USDC buys exactly 500 tokens at 0.1 USDC per token
+    client.purchase_for_usdc(&buyer, &buyer, &50_000_000, &500_000_000, &None);
+    assert_eq!(client.balance(&buyer), 500_000_000);
+    assert_eq!(usdc_token_client.balance(&buyer), 950_000_000);
+
+    // At 0.3 USDC per token, 1 USDC buys 3.333333 tokens costing 0.9999999 USDC
+    client.set_price(&issuer, &3_000_000, &None);
+    client.purchase_for_usdc(&buyer, &buyer, &10_000_000, &3_000_000, &None);
+    assert_eq!(client.balance(&buyer), 503_333_333);
+    assert_eq!(usdc_token_client.balance(&buyer), 940_000_001);
+
+    // Minimum token bound is enforced
+    assert_eq!(
+        client.try_purchase_for_usdc(&buyer, &buyer, &10_000_000, &4_000_000, &None),
+        Err(Ok(Error::from_contract_error(50)))
+    );
+}
+
+#[test]
+fn test_investment_limits_by_class() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let retail = Address::generate(&env);
+    let accredited = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    for investor in [&retail, &accredited] {
+        usdc_token_admin_client.mint(investor, &1_000_000_000);
+        client.set_kyc_status(&admin, investor, &true, &None);
+        client.set_compliance_status(&admin, investor, &ComplianceStatus::Approved, &None);
+    }
+    client.set_investor_class(&admin, &accredited, &InvestorClass::Accredited, &None);
+    assert_eq!(client.investor_class(&retail), InvestorClass::Retail);
+
+    // Retail: 1-10 USDC per purchase, 15 USDC in total; accredited: no per-purchase maximum
+    client.set_investment_limits(&admin, &InvestorClass::Retail, &InvestmentLimits {
+        min_purchase: 10_000_000,
+        max_purchase: 100_000_000,
+        max_total: 150_000_000,
+    }, &None);
+    client.set_investment_limits(&admin, &InvestorClass::Accredited, &InvestmentLimits {
+        min_purchase: 10_000_000,
+        max_purchase: 0,
+        max_total: 0,
+    }, &None);
+
+    // Below minimum ticket size
+    assert_eq!(
+        client.try_purchase(&retail, &retail, &50_000_000, &None),
+        Err(Ok(Error::from_contract_error(53)))
+    );
+
+    // Above per-purchase maximum
+    assert_eq!(
+        client.try_purchase(&retail, &retail, &1_100_000_000, &None),
+        Err(Ok(Error::from_contract_error(54)))
+    );
+
+    // Cumulative cap
+    client.purchase(&retail, &retail, &1_000_000_000, &None);
+    assert_eq!(client.total_invested(&retail), 100_000_000);
+    assert_eq!(
+        client.try_purchase(&retail, &retail, &600_000_000, &None),
+        Err(Ok(Error::from_contract_error(55)))
+    );
+    client.purchase(&retail, &retail, &500_000_000, &None);
+    assert_eq!(client.total_invested(&retail), 150_000_000);
+
+    // Accredited investors have no per-purchase maximum
+    client.purchase(&accredited, &accredited, &5_000_000_000, &None);
+    assert_eq!(client.total_invested(&accredited), 500_000_000);
+}
+
+#[test]
+fn test_refund_releases_investment_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let retail = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&retail, &1_000_000_000);
+    client.set_kyc_status(&admin, &retail, &true, &None);
+    client.set_compliance_status(&admin, &retail, &ComplianceStatus::Approved, &None);
+
+    // Retail investors may invest 10 USDC in total
+    client.set_investment_limits(&admin, &InvestorClass::Retail, &InvestmentLimits {
+        min_purchase: 0,
+        max_purchase: 0,
+        max_total: 100_000_000,
+    }, &None);
+
+    // First offering fails and the investment is refunded
+    env.ledger().set_timestamp(1_000);
+    client.configure_offering(&issuer, &1_000, &2_000, &1_000_000_000, &2_000_000_000, &None);
+    client.purchase(&retail, &retail, &1_000_000_000, &None);
+    assert_eq!(client.total_invested(&retail), 100_000_000);
+    env.ledger().set_timestamp(2_000);
+    client.claim_refund(&retail, &retail);
+    assert_eq!(client.total_invested(&retail), 0);
+
+    // The full cap is available again in the next offering
+    client.configure_offering(&issuer, &2_000, &3_000, &1, &2_000_000_000, &None);
+    client.purchase(&retail, &retail, &1_000_000_000, &None);
+    assert_eq!(client.total_invested(&retail), 100_000_000);
+}
+
+#[test]
+fn test_platform_fee_immediate_and_accrued() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let platform = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    // 2% fee paid at purchase time
+    client.set_fee_config(&admin, &FeeConfig {
+        recipient: platform.clone(),
+        fee_bps: 200,
+        mode: FeeMode::Immediate,
+    }, &None);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+    assert_eq!(usdc_token_client.balance(&platform), 1_000_000);
+    assert_eq!(client.usdc_balance(), 49_000_000);
+
+    // 2% fee accrued for later withdrawal
+    client.set_fee_config(&admin, &FeeConfig {
+        recipient: platform.clone(),
+        fee_bps: 200,
+        mode: FeeMode::Accrued,
+    }, &None);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+    assert_eq!(client.accrued_fees(&platform, &usdc_token_client.address), 1_000_000);
+    assert_eq!(client.usdc_balance(), 98_000_000);
+
+    // Issuer cannot withdraw the platform's fees and vice versa
+    assert_eq!(
+        client.try_withdraw_usdc(&issuer, &99_000_000, &None),
+        Err(Ok(Error::from_contract_error(19)))
+    );
+    assert_eq!(
+        client.try_withdraw_fees(&issuer, &usdc_token_client.address, &1_000_000),
+        Err(Ok(Error::from_contract_error(58)))
+    );
+
+    client.withdraw_fees(&platform, &usdc_token_client.address, &1_000_000);
+    assert_eq!(usdc_token_client.balance(&platform), 2_000_000);
+    assert_eq!(client.accrued_fees(&platform, &usdc_token_client.address), 0);
+
+    client.withdraw_usdc(&issuer, &98_000_000, &None);
+    assert_eq!(usdc_token_client.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_platform_fee_refunded_with_failed_offering() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let platform = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    // Immediate fees are held back while contributions are escrowed
+    client.set_fee_config(&admin, &FeeConfig {
+        recipient: platform.clone(),
+        fee_bps: 200,
+        mode: FeeMode::Immediate,
+    }, &None);
+    env.ledger().set_timestamp(1_000);
+    client.configure_offering(&issuer, &1_000, &2_000, &100_000_000, &200_000_000, &None);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+    assert_eq!(usdc_token_client.balance(&platform), 0);
+    assert_eq!(client.accrued_fees(&platform, &usdc_token_client.address), 1_000_000);
+    assert_eq!(
+        client.try_withdraw_fees(&platform, &usdc_token_client.address, &1_000_000),
+        Err(Ok(Error::from_contract_error(46)))
+    );
+
+    // Failed offering refunds the full contribution, including the fee
+    env.ledger().set_timestamp(2_000);
+    client.claim_refund(&buyer, &buyer);
+    assert_eq!(usdc_token_client.balance(&buyer), 1_000_000_000);
+    assert_eq!(client.accrued_fees(&platform, &usdc_token_client.address), 0);
+    assert_eq!(client.usdc_balance(), 0);
+}
+
+#[test]
+fn test_fee_recipient_change_keeps_accrued_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let platform = Address::generate(&env);
+    let platform2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    client.set_fee_config(&admin, &FeeConfig {
+        recipient: platform.clone(),
+        fee_bps: 200,
+        mode: FeeMode::Accrued,
+    }, &None);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+    assert_eq!(client.accrued_fees(&platform, &usdc_token_client.address), 1_000_000);
+
+    // Admins cannot redirect the fee stream without a justification
+    let redirected = FeeConfig {
+        recipient: issuer.clone(),
+        fee_bps: 200,
+        mode: FeeMode::Accrued,
+    };
+    assert_eq!(
+        client.try_set_fee_config(&admin, &redirected, &None),
+        Err(Ok(Error::from_contract_error(58)))
+    );
+    assert_eq!(
+        client.try_set_fee_config(&issuer, &redirected, &None),
+        Err(Ok(Error::from_contract_error(58)))
+    );
+
+    // The current recipient hands over; fees it already earned stay with it
+    client.set_fee_config(&platform, &FeeConfig {
+        recipient: platform2.clone(),
+        fee_bps: 200,
+        mode: FeeMode::Accrued,
+    }, &None);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+    assert_eq!(client.accrued_fees(&platform, &usdc_token_client.address), 1_000_000);
+    assert_eq!(client.accrued_fees(&platform2, &usdc_token_client.address), 1_000_000);
+    assert_eq!(client.total_accrued_fees(&usdc_token_client.address), 2_000_000);
+
+    client.withdraw_fees(&platform, &usdc_token_client.address, &1_000_000);
+    assert_eq!(usdc_token_client.balance(&platform), 1_000_000);
+    assert_eq!(
+        client.try_withdraw_fees(&issuer, &usdc_token_client.address, &1),
+        Err(Ok(Error::from_contract_error(58)))
+    );
+    assert_eq!(client.usdc_reconciliation().surplus, 0);
+}
+
+#[test]
+fn test_admin_rotates_lost_fee_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let platform = Address::generate(&env);
+    let platform2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    client.set_fee_config(&admin, &FeeConfig {
+        recipient: platform.clone(),
+        fee_bps: 200,
+        mode: FeeMode::Accrued,
+    }, &None);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+
+    // The platform key is lost, so the issuer rotates the recipient on the record
+    let justification = Justification {
+        reason_code: 7,
+        document_hash: BytesN::from_array(&env, &[3; 32]),
+    };
+    client.set_fee_config(&issuer, &FeeConfig {
+        recipient: platform2.clone(),
+        fee_bps: 200,
+        mode: FeeMode::Accrued,
+    }, &Some(justification));
+    assert_eq!(client.fee_config().unwrap().recipient, platform2);
+    let entry = client.audit_log(&(client.audit_log_len() - 1), &1).get(0).unwrap();
+    assert_eq!(entry.action, symbol_short!("feecfg"));
+    assert_eq!(entry.caller, issuer);
+    assert_eq!(entry.reason_code, Some(7));
+
+    // New fees go to the new recipient; earlier fees stay with the old one
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+    assert_eq!(client.accrued_fees(&platform, &usdc_token_client.address), 1_000_000);
+    assert_eq!(client.accrued_fees(&platform2, &usdc_token_client.address), 1_000_000);
+}
+
+#[test]
+fn test_broker_referral_commission() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let broker = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    // Unapproved referrers are rejected
+    assert_eq!(
+        client.try_purchase(&buyer, &buyer, &500_000_000, &Some(broker.clone())),
+        Err(Ok(Error::from_contract_error(60)))
+    );
+
+    // Broker earns 5% on referred sales
+    client.approve_broker(&admin, &broker, &500, &None);
+    client.purchase(&buyer, &buyer, &500_000_000, &Some(broker.clone()));
+    client.purchase(&buyer, &buyer, &300_000_000, &Some(broker.clone()));
+
+    let sales = client.broker_sales(&broker, &usdc_token_client.address);
+    assert_eq!(sales.volume, 80_000_000);
+    assert_eq!(sales.tokens_sold, 800_000_000);
+    assert_eq!(sales.accrued, 4_000_000);
+    assert_eq!(client.usdc_balance(), 76_000_000);
+    assert_eq!(client.unclaimed_commissions(&usdc_token_client.address), 4_000_000);
+
+    // Broker claims commission
+    client.claim_commission(&broker, &usdc_token_client.address);
+    assert_eq!(usdc_token_client.balance(&broker), 4_000_000);
+    let sales = client.broker_sales(&broker, &usdc_token_client.address);
+    assert_eq!(sales.accrued, 0);
+    assert_eq!(sales.claimed, 4_000_000);
+    assert_eq!(client.unclaimed_commissions(&usdc_token_client.address), 0);
+    assert_eq!(
+        client.try_claim_commission(&broker, &usdc_token_client.address),
+        Err(Ok(Error::from_contract_error(62)))
+    );
+
+    // Revoked brokers can no longer refer purchases
+    client.revoke_broker(&admin, &broker, &None);
+    assert_eq!(
+        client.try_purchase(&buyer, &buyer, &100_000_000, &Some(broker.clone())),
+        Err(Ok(Error::from_contract_error(60)))
+    );
+
+    // Buyers cannot refer themselves
+    client.approve_broker(&admin, &buyer, &500, &None);
+    assert_eq!(
+        client.try_purchase(&buyer, &buyer, &100_000_000, &Some(buyer.clone())),
+        Err(Ok(Error::from_contract_error(61)))
+    );
+}
+
+#[test]
+fn test_fee_and_commission_limited_together() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let broker = Address::generate(&env);
+    let platform = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // A 90% fee leaves room for at most a 10% commission
+    client.set_fee_config(&admin, &FeeConfig {
+        recipient: platform.clone(),
+        fee_bps: 9_000,
+        mode: FeeMode::Accrued,
+    }, &None);
+    assert_eq!(
+        client.try_approve_broker(&admin, &broker, &1_001, &None),
+        Err(Ok(Error::from_contract_error(33)))
+    );
+    client.approve_broker(&admin, &broker, &1_000, &None);
+
+    // The fee cannot be raised past the approved commission
+    let raised = FeeConfig {
+        recipient: platform.clone(),
+        fee_bps: 9_001,
+        mode: FeeMode::Accrued,
+    };
+    assert_eq!(
+        client.try_set_fee_config(&admin, &raised, &None),
+        Err(Ok(Error::from_contract_error(33)))
+    );
+
+    // Once the broker is revoked the fee can be raised
+    client.revoke_broker(&admin, &broker, &None);
+    client.set_fee_config(&admin, &raised, &None);
+}
+
+#[test]
+fn test_broker_commission_reversed_on_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let broker = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+    client.approve_broker(&admin, &broker, &500, &None);
+
+    env.ledger().set_timestamp(1_000);
+    client.configure_offering(&issuer, &1_000, &2_000, &100_000_000, &200_000_000, &None);
+    client.purchase(&buyer, &buyer, &500_000_000, &Some(broker.clone()));
+
+    // Escrowed commission cannot be claimed
+    assert_eq!(client.broker_sales(&broker, &usdc_token_client.address).accrued, 2_500_000);
+    assert_eq!(
+        client.try_claim_commission(&broker, &usdc_token_client.address),
+        Err(Ok(Error::from_contract_error(62)))
+    );
+
+    // Failed offering reverses the referred sale
+    env.ledger().set_timestamp(2_000);
+    client.claim_refund(&buyer, &buyer);
+    assert_eq!(usdc_token_client.balance(&buyer), 1_000_000_000);
+    let sales = client.broker_sales(&broker, &usdc_token_client.address);
+    assert_eq!(sales.volume, 0);
+    assert_eq!(sales.accrued, 0);
+    assert_eq!(client.usdc_balance(), 0);
+}
+
+#[test]
+fn test_withdraw_usdc_to_destinations_and_split() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let escrow = Address::generate(&env);
+    let operating = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+    client.purchase(&buyer, &buyer, &1_000_000_000, &None);
+    assert_eq!(client.usdc_balance(), 100_000_000);
+
+    // Destinations must be allowlisted
+    assert_eq!(
+        client.try_withdraw_usdc_to(&issuer, &escrow, &10_000_000, &None),
+        Err(Ok(Error::from_contract_error(63)))
+    );
+    client.add_withdrawal_destination(&issuer, &escrow, &None);
+    client.add_withdrawal_destination(&issuer, &operating, &None);
+    assert!(client.is_withdrawal_destination(&escrow));
+
+    client.withdraw_usdc_to(&issuer, &escrow, &10_000_000, &None);
+    assert_eq!(usdc_token_client.balance(&escrow), 10_000_000);
+
+    // Split 30 USDC: 1/3 to escrow, 1/3 to operating, remainder to issuer
+    let mut splits = Vec::new(&env);
+    splits.push_back((escrow.clone(), 3_333u32));
+    splits.push_back((operating.clone(), 3_333u32));
+    splits.push_back((issuer.clone(), 3_334u32));
+    client.withdraw_usdc_split(&issuer, &30_000_000, &splits, &None);
+    assert_eq!(usdc_token_client.balance(&escrow), 19_999_000);
+    assert_eq!(usdc_token_client.balance(&operating), 9_999_000);
+    assert_eq!(usdc_token_client.balance(&issuer), 10_002_000);
+    assert_eq!(client.usdc_balance(), 60_000_000);
+
+    // Splits must add up to 100%
+    let mut bad_splits = Vec::new(&env);
+    bad_splits.push_back((escrow.clone(), 5_000u32));
+    assert_eq!(
+        client.try_withdraw_usdc_split(&issuer, &10_000_000, &bad_splits, &None),
+        Err(Ok(Error::from_contract_error(64)))
+    );
+
+    // Removed destinations are rejected
+    client.remove_withdrawal_destination(&issuer, &operating, &None);
+    assert_eq!(
+        client.try_withdraw_usdc_to(&issuer, &operating, &10_000_000, &None),
+        Err(Ok(Error::from_contract_error(63)))
+    );
+}
+
+#[test]
+fn test_withdrawal_period_cap_and_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let guardian = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+    client.purchase(&buyer, &buyer, &10_000_000_000, &None);
+    assert_eq!(client.usdc_balance(), 1_000_000_000);
+
+    // Guardian takes over the policy: 80 USDC per 100 ledgers, queue above 60 USDC
+    client.set_guardian(&issuer, &guardian, &None);
+    let policy = WithdrawalPolicy {
+        period_ledgers: 100,
+        period_cap: 800_000_000,
+        timelock_threshold: 600_000_000,
+        timelock_ledgers: 50,
+    };
+    assert_eq!(
+        client.try_set_withdrawal_policy(&issuer, &policy, &None),
+        Err(Ok(Error::from_contract_error(65)))
+    );
+    client.set_withdrawal_policy(&guardian, &policy, &None);
+
+    // Large withdrawals must be queued
+    assert_eq!(
+        client.try_withdraw_usdc(&issuer, &700_000_000, &None),
+        Err(Ok(Error::from_contract_error(68)))
+    );
+
+    // Rolling window cap
+    client.withdraw_usdc(&issuer, &500_000_000, &None);
+    client.withdraw_usdc(&issuer, &300_000_000, &None);
+    assert_eq!(
+        client.try_withdraw_usdc(&issuer, &100_000_000, &None),
+        Err(Ok(Error::from_contract_error(67)))
+    );
+    env.ledger().set_sequence_number(env.ledger().sequence() + 100);
+    client.withdraw_usdc(&issuer, &200_000_000, &None);
+    assert_eq!(client.usdc_balance(), 0);
+}
+
+#[test]
+fn test_withdrawal_policy_loosening_requires_guardian() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let guardian = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // Issuer sets an initial policy before a guardian is appointed
+    let policy = WithdrawalPolicy {
+        period_ledgers: 100,
+        period_cap: 800_000_000,
+        timelock_threshold: 600_000_000,
+        timelock_ledgers: 50,
+    };
+    client.set_withdrawal_policy(&issuer, &policy, &None);
+
+    // Raising or removing the cap needs a guardian
+    let mut loosened = policy.clone();
+    loosened.period_cap = 0;
+    assert_eq!(
+        client.try_set_withdrawal_policy(&issuer, &loosened, &None),
+        Err(Ok(Error::from_contract_error(104)))
+    );
+    let mut loosened = policy.clone();
+    loosened.timelock_ledgers = 10;
+    assert_eq!(
+        client.try_set_withdrawal_policy(&issuer, &loosened, &None),
+        Err(Ok(Error::from_contract_error(104)))
+    );
+
+    // Tightening is allowed
+    let mut tightened = policy.clone();
+    tightened.period_cap = 500_000_000;
+    client.set_withdrawal_policy(&issuer, &tightened, &None);
+
+    // The guardian can loosen the policy
+    client.set_guardian(&issuer, &guardian, &None);
+    client.set_withdrawal_policy(&guardian, &policy, &None);
+    assert_eq!(client.withdrawal_policy(), Some(policy));
+}
+
+#[test]
+fn test_queued_withdrawal_execute_and_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let guardian = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+    client.purchase(&buyer, &buyer, &10_000_000_000, &None);
+
+    client.set_guardian(&issuer, &guardian, &None);
+    client.set_withdrawal_policy(&guardian, &WithdrawalPolicy {
+        period_ledgers: 0,
+        period_cap: 0,
+        timelock_threshold: 100_000_000,
+        timelock_ledgers: 50,
+    }, &None);
+
+    // Queued withdrawal executes only after the delay
+    let id = client.queue_withdrawal(&issuer, &issuer, &700_000_000, &None);
+    assert_eq!(
+        client.try_execute_withdrawal(&issuer, &id, &None),
+        Err(Ok(Error::from_contract_error(70)))
+    );
+    env.ledger().set_sequence_number(env.ledger().sequence() + 50);
+    client.execute_withdrawal(&issuer, &id, &None);
+    assert_eq!(usdc_token_client.balance(&issuer), 700_000_000);
+    assert_eq!(client.queued_withdrawal(&id), None);
+
+    // Guardian cancels a suspicious withdrawal
+    let id = client.queue_withdrawal(&issuer, &issuer, &300_000_000, &None);
+    client.cancel_withdrawal(&guardian, &id, &None);
+    env.ledger().set_sequence_number(env.ledger().sequence() + 50);
+    assert_eq!(
+        client.try_execute_withdrawal(&issuer, &id, &None),
+        Err(Ok(Error::from_contract_error(69)))
+    );
+    assert_eq!(client.usdc_balance(), 300_000_000);
+}
+
+#[test]
+fn test_reconcile_usdc_surplus() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let broker = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    // Purchase with an accrued 1% fee and a 5% broker commission
+    client.set_fee_config(&admin, &FeeConfig {
+        recipient: fee_recipient.clone(),
+        fee_bps: 100,
+        mode: FeeMode::Accrued,
+    }, &None);
+    client.approve_broker(&admin, &broker, &500, &None);
+    client.purchase(&buyer, &buyer, &1_000_000_000, &Some(broker.clone()));
+
+    // Tracked liabilities match the actual balance
+    assert_eq!(
+        client.usdc_reconciliation(),
+        Reconciliation { tracked: 100_000_000, actual: 100_000_000, surplus: 0 }
+    );
+    assert_eq!(
+        client.try_reconcile_usdc(&admin, &None),
+        Err(Ok(Error::from_contract_error(72)))
+    );
+
+    // USDC sent directly to the contract shows up as surplus
+    usdc_token_admin_client.mint(&contract_id, &25_000_000);
+    assert_eq!(client.usdc_reconciliation().surplus, 25_000_000);
+    assert_eq!(
+        client.try_reconcile_usdc(&buyer, &None),
+        Err(Ok(Error::from_contract_error(71)))
+    );
+
+    // Reconciling credits the surplus to the issuer
+    assert_eq!(client.reconcile_usdc(&admin, &None), 25_000_000);
+    assert_eq!(client.usdc_balance(), 119_000_000);
+    assert_eq!(client.usdc_reconciliation().surplus, 0);
+    client.withdraw_usdc(&issuer, &119_000_000, &None);
+    assert_eq!(usdc_token_client.balance(&issuer), 119_000_000);
+}
+
+#[test]
+fn test_sweep_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let recovery = Address::generate(&env);
+
+    // Setup test USDC and EURC token contracts
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+    let (eurc_token_client, eurc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // Issuer holds EURC proceeds from a purchase
+    client.set_payment_asset(&admin, &eurc_token_client.address, &90_000, &7, &None);
+    eurc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+    client.purchase_with_asset(&buyer, &buyer, &500_000_000, &eurc_token_client.address, &None);
+
+    // EURC sent by mistake can be swept, but not the issuer's proceeds
+    eurc_token_admin_client.mint(&contract_id, &10_000_000);
+    assert_eq!(
+        client.try_sweep_token(&admin, &eurc_token_client.address, &recovery, &10_000_001, &None),
+        Err(Ok(Error::from_contract_error(19)))
+    );
+    client.sweep_token(&admin, &eurc_token_client.address, &recovery, &10_000_000, &None);
+    assert_eq!(eurc_token_client.balance(&recovery), 10_000_000);
+    assert_eq!(eurc_token_client.balance(&contract_id), 45_000_000);
+
+    // USDC cannot be swept
+    assert_eq!(
+        client.try_sweep_token(&admin, &usdc_token_client.address, &recovery, &1, &None),
+        Err(Ok(Error::from_contract_error(73)))
+    );
+}
+
+#[test]
+fn test_onboard_investors_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    let mut records = Vec::new(&env);
+    records.push_back(OnboardingRecord {
+        address: user1.clone(),
+        kyc_verified: true,
+        compliance: ComplianceStatus::Approved,
+        jurisdiction: symbol_short!("US"),
+    });
+    records.push_back(OnboardingRecord {
+        address: user2.clone(),
+        kyc_verified: true,
+        compliance: ComplianceStatus::Pending,
+        jurisdiction: symbol_short!("DE"),
+    });
+
+    // Only admins can onboard
+    assert_eq!(
+        client.try_onboard_investors(&user1, &records, &None),
+        Err(Ok(Error::from_contract_error(3)))
+    );
+
+    client.onboard_investors(&admin, &records, &None);
+    assert!(client.is_kyc_verified(&user1));
+    assert_eq!(client.check_compliance(&user1), ComplianceStatus::Approved);
+    assert_eq!(client.jurisdiction(&user1), Some(symbol_short!("US")));
+    assert!(client.is_kyc_verified(&user2));
+    assert_eq!(client.check_compliance(&user2), ComplianceStatus::Pending);
+    assert_eq!(client.jurisdiction(&user2), Some(symbol_short!("DE")));
+
+    // Empty batches are rejected
+    assert_eq!(
+        client.try_onboard_investors(&admin, &Vec::new(&env), &None),
+        Err(Ok(Error::from_contract_error(74)))
+    );
+}
+
+#[test]
+fn test_onboard_investors_is_atomic() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // user1 is approved and authorization becomes non-revocable
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.configure_authorization(&admin, &true, &false, &None);
+
+    // A batch revoking user1 fails and leaves user2 untouched
+    let mut records = Vec::new(&env);
+    records.push_back(OnboardingRecord {
+        address: user2.clone(),
+        kyc_verified: true,
+        compliance: ComplianceStatus::Approved,
+        jurisdiction: symbol_short!("GB"),
+    });
+    records.push_back(OnboardingRecord {
+        address: user1.clone(),
+        kyc_verified: false,
+        compliance: ComplianceStatus::Approved,
+        jurisdiction: symbol_short!("GB"),
+    });
+    assert_eq!(
+        client.try_onboard_investors(&admin, &records, &None),
+        Err(Ok(Error::from_contract_error(25)))
+    );
+    assert!(!client.is_kyc_verified(&user2));
+    assert_eq!(client.jurisdiction(&user2), None);
+
+    // Oversized batches are rejected
+    let mut records = Vec::new(&env);
+    for _ in 0..26 {
+        records.push_back(OnboardingRecord {
+            address: Address::generate(&env),
+            kyc_verified: true,
+            compliance: ComplianceStatus::Approved,
+            jurisdiction: symbol_short!("GB"),
+        });
+    }
+    assert_eq!(
+        client.try_onboard_investors(&admin, &records, &None),
+        Err(Ok(Error::from_contract_error(74)))
+    );
+}
+
+#[test]
+fn test_onboard_investors_max_batch_fits_write_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    let mut records = Vec::new(&env);
+    for _ in 0..MAX_ONBOARDING_BATCH_SIZE {
+        records.push_back(OnboardingRecord {
+            address: Address::generate(&env),
+            kyc_verified: true,
+            compliance: ComplianceStatus::Approved,
+            jurisdiction: symbol_short!("GB"),
+        });
+    }
+
+    // A full batch stays within the per-transaction write limit
+    client.onboard_investors(&admin, &records, &None);
+    assert!(env.cost_estimate().resources().write_entries <= TX_WRITE_ENTRY_LIMIT);
+
+    // One more record is rejected
+    records.push_back(OnboardingRecord {
+        address: Address::generate(&env),
+        kyc_verified: true,
+        compliance: ComplianceStatus::Approved,
+        jurisdiction: symbol_short!("GB"),
+    });
+    assert_eq!(
+        client.try_onboard_investors(&admin, &records, &None),
+        Err(Ok(Error::from_contract_error(74)))
+    );
+}
+
+#[test]
+fn test_batch_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    // Issuer airdrops to two investors, one of them twice
+    let mut transfers = Vec::new(&env);
+    transfers.push_back((user1.clone(), 100_000));
+    transfers.push_back((user2.clone(), 200_000));
+    transfers.push_back((user1.clone(), 50_000));
+    client.batch_transfer(&issuer, &transfers);
+
+    assert_eq!(client.balance(&user1), 150_000);
+    assert_eq!(client.balance(&user2), 200_000);
+    assert_eq!(client.balance(&issuer), 1_000_000_000_000 - 350_000);
+}
+
+#[test]
+fn test_batch_transfer_is_atomic() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // user2 is not KYC verified
+    client.set_kyc_status(&admin, &issuer, &true, &None);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &issuer, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    let mut transfers = Vec::new(&env);
+    transfers.push_back((user1.clone(), 100_000));
+    transfers.push_back((user2.clone(), 100_000));
+    assert_eq!(
+        client.try_batch_transfer(&issuer, &transfers),
+        Err(Ok(Error::from_contract_error(12)))
+    );
+    assert_eq!(client.balance(&user1), 0);
+
+    // Total exceeding the sender's balance fails
+    let mut transfers = Vec::new(&env);
+    transfers.push_back((issuer.clone(), 100_000));
+    assert_eq!(
+        client.try_batch_transfer(&user1, &transfers),
+        Err(Ok(Error::from_contract_error(14)))
+    );
+
+    // Empty batches are rejected
+    assert_eq!(
+        client.try_batch_transfer(&issuer, &Vec::new(&env)),
+        Err(Ok(Error::from_contract_error(74)))
+    );
+}
+
+#[test]
+fn test_batch_transfer_max_batch_fits_write_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    // A split and a passed coupon date both leave every recipient with state to settle
+    client.split(&admin, &2, &1, &None);
+    let period = 15_768_000;
+    env.ledger().set_timestamp(1_000);
+    client.set_debt_terms(
+        &issuer,
+        &DebtTerms {
+            face_value: 1_000_000_000,
+            coupon_bps: 500,
+            period,
+            first_payment: 1_000 + period,
+            maturity: 1_000 + 2 * period,
+        },
+        &None,
+    );
+    env.ledger().set_timestamp(1_000 + period);
+
+    let mut records = Vec::new(&env);
+    let mut transfers = Vec::new(&env);
+    records.push_back(OnboardingRecord {
+        address: issuer.clone(),
+        kyc_verified: true,
+        compliance: ComplianceStatus::Approved,
+        jurisdiction: symbol_short!("GB"),
+    });
+    for _ in 0..MAX_TRANSFER_BATCH_SIZE {
+        let recipient = Address::generate(&env);
+        if records.len() == MAX_ONBOARDING_BATCH_SIZE {
+            client.onboard_investors(&admin, &records, &None);
+            records = Vec::new(&env);
+        }
+        records.push_back(OnboardingRecord {
+            address: recipient.clone(),
+            kyc_verified: true,
+            compliance: ComplianceStatus::Approved,
+            jurisdiction: symbol_short!("GB"),
+        });
+        transfers.push_back((recipient, 100_000));
+    }
+    client.onboard_investors(&admin, &records, &None);
+
+    // A full batch of new holders stays within the per-transaction write limit
+    client.batch_transfer(&issuer, &transfers);
+    assert!(env.cost_estimate().resources().write_entries <= TX_WRITE_ENTRY_LIMIT);
+    assert_eq!(client.holder_count(), MAX_TRANSFER_BATCH_SIZE + 1);
+
+    // One more recipient is rejected
+    transfers.push_back((admin.clone(), 100_000));
+    assert_eq!(
+        client.try_batch_transfer(&issuer, &transfers),
+        Err(Ok(Error::from_contract_error(74)))
+    );
+}
+
+#[test]
+fn test_holder_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // Issuer holds the initial supply
+    assert_eq!(client.holder_count(), 1);
+    assert_eq!(client.holders(&0, &10), vec![&env, issuer.clone()]);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    // New holders are appended
+    client.transfer(&issuer, &user1, &100_000);
+    client.transfer(&issuer, &user2, &100_000);
+    assert_eq!(client.holder_count(), 3);
+    assert_eq!(client.holders(&1, &10), vec![&env, user1.clone(), user2.clone()]);
+
+    // A holder going to zero is removed and the last holder takes its slot
+    client.transfer(&user1, &user2, &100_000);
+    assert_eq!(client.holder_count(), 2);
+    assert_eq!(client.holders(&0, &10), vec![&env, issuer.clone(), user2.clone()]);
+
+    // Clawback to zero removes the holder too
+    client.clawback(&admin, &user2, &200_000, &None);
+    assert_eq!(client.holder_count(), 1);
+    assert_eq!(client.holders(&0, &10), vec![&env, issuer.clone()]);
+}
+
+#[test]
+fn test_holder_info() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+    client.transfer(&issuer, &user1, &100_000);
+
+    assert_eq!(
+        client.holder_info(&user1),
+        HolderInfo {
+            address: user1.clone(),
+            balance: 100_000,
+            kyc_verified: true,
+            compliance: ComplianceStatus::Approved,
+            frozen: false,
+        }
+    );
+
+    // Suspended holders are reported as frozen
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Suspended, &None);
+    assert!(client.holder_info(&user1).frozen);
+}
+
+#[test]
+fn test_sep41_transfer_and_clawback_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    // Transfer: topics (transfer, from, to), data amount
+    client.transfer(&issuer, &user1, &100_000);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("transfer"), issuer.clone(), user1.clone()).into_val(&env),
+                100_000_i128.into_val(&env),
+            ),
+        ]
+    );
+
+    // Clawback: a transfer from the holder back to the issuer, with no new supply
+    client.clawback(&admin, &user1, &40_000, &None);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("transfer"), user1.clone(), issuer.clone()).into_val(&env),
+                40_000_i128.into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.event_schema_version(), 2);
+}
+
+#[test]
+fn test_admin_events_include_caller() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_transfer_restriction(&admin, &false, &None);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("restrict"), admin.clone()).into_val(&env),
+                (2_u32, SecurityTokenEvent::TransferRestrictionChanged(admin.clone(), false)).into_val(&env),
+            ),
+        ]
+    );
+
+    client.bump_instance_ttl(&admin, &None);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("bumpinst"), admin.clone()).into_val(&env),
+                (2_u32, SecurityTokenEvent::InstanceTtlBumped(admin.clone())).into_val(&env),
+            ),
+        ]
+    );
+
+    // Lifecycle events carry the schema version too
+    client.split(&admin, &2, &1, &None);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("split"), admin.clone()).into_val(&env),
+                (2_u32, LifecycleEvent::Split(2, 1, 2_000_000_000_000, 50_000)).into_val(&env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_audit_log_records_justification() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    let court_order = BytesN::from_array(&env, &[7; 32]);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+    client.transfer(&issuer, &user1, &100_000);
+
+    // Clawback with a court order reference
+    client.clawback(
+        &admin,
+        &user1,
+        &100_000,
+        &Some(Justification { reason_code: 3, document_hash: court_order.clone() }),
+    );
+
+    assert_eq!(client.audit_log_len(), 4);
+    let entries = client.audit_log(&3, &10);
+    assert_eq!(
+        entries.get(0).unwrap(),
+        AuditEntry {
+            action: symbol_short!("clawback"),
+            caller: admin.clone(),
+            subject: Some(user1.clone()),
+            reason_code: Some(3),
+            document_hash: Some(court_order),
+            timestamp: env.ledger().timestamp(),
+        }
+    );
+
+    // Global actions have no subject
+    let entry = client.audit_log(&2, &1).get(0).unwrap();
+    assert_eq!(entry.action, symbol_short!("restrict"));
+    assert_eq!(entry.subject, None);
+    assert_eq!(entry.reason_code, None);
+
+    // Treasury, pricing and broker changes are audited too
+    let board_minutes = BytesN::from_array(&env, &[9; 32]);
+    client.set_price(
+        &issuer,
+        &110_000,
+        &Some(Justification { reason_code: 5, document_hash: board_minutes.clone() }),
+    );
+    client.approve_broker(&admin, &user1, &100, &None);
+    assert_eq!(client.audit_log_len(), 6);
+    let entry = client.audit_log(&4, &1).get(0).unwrap();
+    assert_eq!(entry.action, symbol_short!("price"));
+    assert_eq!(entry.caller, issuer);
+    assert_eq!(entry.document_hash, Some(board_minutes));
+    let entry = client.audit_log(&5, &1).get(0).unwrap();
+    assert_eq!(entry.action, symbol_short!("broker"));
+    assert_eq!(entry.subject, Some(user1.clone()));
+}
+
+#[test]
+fn test_audit_log_by_subject() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    let case_file = BytesN::from_array(&env, &[1; 32]);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
+    client.set_kyc_status(
+        &admin,
+        &user1,
+        &false,
+        &Some(Justification { reason_code: 1, document_hash: case_file.clone() }),
+    );
+
+    // Only user1's entries are returned, in order
+    assert_eq!(client.subject_audit_len(&user1), 2);
+    let entries = client.subject_audit_log(&user1, &0, &10);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries.get(0).unwrap().reason_code, None);
+    assert_eq!(entries.get(1).unwrap().action, symbol_short!("kyc"));
+    assert_eq!(entries.get(1).unwrap().document_hash, Some(case_file));
+    assert_eq!(client.subject_audit_len(&user2), 1);
+
+    // Failed actions are not logged
+    client.configure_authorization(&admin, &true, &false, &None);
+    assert_eq!(
+        client.try_set_kyc_status(&admin, &user2, &false, &None),
+        Err(Ok(Error::from_contract_error(25)))
+    );
+    assert_eq!(client.subject_audit_len(&user2), 1);
+    assert_eq!(client.audit_log_len(), 4);
+}
+
+#[test]
+fn test_every_admin_entrypoint_is_audited() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let pricer = Address::generate(&env);
+    let broker = Address::generate(&env);
+    let platform = Address::generate(&env);
+    let destination = Address::generate(&env);
+    let recovery = Address::generate(&env);
+
+    // Setup test USDC and EURC token contracts
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+    let (eurc_token_client, eurc_token_admin_client) = create_token_contract(&env, &admin);
+    usdc_token_admin_client.mint(&user1, &10_000_000_000);
+    eurc_token_admin_client.mint(&user1, &1_000_000_000);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+    env.ledger().set_timestamp(1_000);
+    let mut audited = client.audit_log_len();
+
+    // Investor administration
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    assert_audited(&client, &mut audited, symbol_short!("kyc"));
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    assert_audited(&client, &mut audited, symbol_short!("complianc"));
+    let records = vec![&env, OnboardingRecord {
+        address: user2.clone(),
+        kyc_verified: true,
+        compliance: ComplianceStatus::Approved,
+        jurisdiction: symbol_short!("GB"),
+    }];
+    client.onboard_investors(&admin, &records, &None);
+    assert_audited(&client, &mut audited, symbol_short!("onboard"));
+    client.set_investor_class(&admin, &user1, &InvestorClass::Accredited, &None);
+    assert_audited(&client, &mut audited, symbol_short!("invclass"));
+    client.set_investment_limits(&admin, &InvestorClass::Institutional, &InvestmentLimits {
+        min_purchase: 10_000_000,
+        max_purchase: 0,
+        max_total: 0,
+    }, &None);
+    assert_audited(&client, &mut audited, symbol_short!("limits"));
+    client.configure_authorization(&admin, &false, &true, &None);
+    assert_audited(&client, &mut audited, symbol_short!("auth"));
+    client.set_transfer_restriction(&admin, &false, &None);
+    assert_audited(&client, &mut audited, symbol_short!("restrict"));
+    client.add_admin(&issuer, &new_admin, &None);
+    assert_audited(&client, &mut audited, symbol_short!("admin"));
+    client.remove_admin(&issuer, &new_admin, &None);
+    assert_audited(&client, &mut audited, symbol_short!("adminrem"));
+
+    // Pricing
+    client.add_pricer(&issuer, &pricer, &None);
+    assert_audited(&client, &mut audited, symbol_short!("pricer"));
+    client.set_max_price_change(&issuer, &5_000, &None);
+    assert_audited(&client, &mut audited, symbol_short!("pricemax"));
+    client.set_price(&pricer, &120_000, &None);
+    assert_audited(&client, &mut audited, symbol_short!("price"));
+    client.remove_pricer(&issuer, &pricer, &None);
+    assert_audited(&client, &mut audited, symbol_short!("pricerrem"));
+    let oracle_id = env.register(MockOracle, ());
+    client.set_oracle(&issuer, &OracleConfig {
+        oracle: oracle_id,
+        asset: OracleAsset::Other(symbol_short!("NAV")),
+        max_age: 3_600,
+        on_stale: StalePriceAction::Halt,
+    }, &None);
+    assert_audited(&client, &mut audited, symbol_short!("oracle"));
+    client.clear_oracle(&issuer, &None);
+    assert_audited(&client, &mut audited, symbol_short!("oraclerem"));
+
+    // Payment assets, fees and brokers
+    client.set_payment_asset(&admin, &eurc_token_client.address, &90_000, &7, &None);
+    assert_audited(&client, &mut audited, symbol_short!("payasset"));
+    client.set_fee_config(&admin, &FeeConfig {
+        recipient: platform.clone(),
+        fee_bps: 100,
+        mode: FeeMode::Immediate,
+    }, &None);
+    assert_audited(&client, &mut audited, symbol_short!("feecfg"));
+    client.approve_broker(&admin, &broker, &500, &None);
+    assert_audited(&client, &mut audited, symbol_short!("broker"));
+    client.revoke_broker(&admin, &broker, &None);
+    assert_audited(&client, &mut audited, symbol_short!("brokerrev"));
+    client.purchase_with_asset(&user1, &user1, &500_000_000, &eurc_token_client.address, &None);
+    audited = client.audit_log_len();
+    let eurc_proceeds = client.asset_balance(&eurc_token_client.address);
+    client.withdraw_asset(&issuer, &eurc_token_client.address, &eurc_proceeds, &None);
+    assert_audited(&client, &mut audited, symbol_short!("withdrawa"));
+    client.remove_payment_asset(&admin, &eurc_token_client.address, &None);
+    assert_audited(&client, &mut audited, symbol_short!("payassetr"));
+    eurc_token_admin_client.mint(&contract_id, &10_000_000);
+    client.sweep_token(&admin, &eurc_token_client.address, &recovery, &10_000_000, &None);
+    assert_audited(&client, &mut audited, symbol_short!("sweep"));
+
+    // Disclosure and metadata
+    let memo = symbol_short!("memo");
+    let memo_hash = BytesN::from_array(&env, &[7; 32]);
+    client.set_document(&admin, &memo, &String::from_str(&env, "ipfs://memo"), &memo_hash, &None);
+    assert_audited(&client, &mut audited, symbol_short!("docset"));
+    client.set_active_terms(&admin, &memo_hash, &None);
+    assert_audited(&client, &mut audited, symbol_short!("terms"));
+    client.clear_active_terms(&admin, &None);
+    assert_audited(&client, &mut audited, symbol_short!("termsrem"));
+    client.remove_document(&admin, &memo, &None);
+    assert_audited(&client, &mut audited, symbol_short!("docrem"));
+    client.update_metadata(&issuer, &Some(String::from_str(&env, "Renamed Token")), &None, &None, &None);
+    assert_audited(&client, &mut audited, symbol_short!("metadata"));
+    client.lock_metadata_field(&issuer, &MetadataField::Symbol, &None);
+    assert_audited(&client, &mut audited, symbol_short!("metalock"));
+
+    // Treasury
+    client.purchase(&user1, &user1, &5_000_000_000, &None);
+    audited = client.audit_log_len();
+    client.withdraw_usdc(&issuer, &10_000_000, &None);
+    assert_audited(&client, &mut audited, symbol_short!("withdraw"));
+    client.add_withdrawal_destination(&issuer, &destination, &None);
+    assert_audited(&client, &mut audited, symbol_short!("wdest"));
+    client.withdraw_usdc_to(&issuer, &destination, &10_000_000, &None);
+    assert_audited(&client, &mut audited, symbol_short!("withdrawt"));
+    client.withdraw_usdc_split(&issuer, &10_000_000, &vec![&env, (destination.clone(), 10_000)], &None);
+    assert_audited(&client, &mut audited, symbol_short!("wdsplit"));
+    client.set_guardian(&issuer, &guardian, &None);
+    assert_audited(&client, &mut audited, symbol_short!("guardian"));
+    client.set_withdrawal_policy(&guardian, &WithdrawalPolicy {
+        period_ledgers: 100,
+        period_cap: 0,
+        timelock_threshold: 1,
+        timelock_ledgers: 10,
+    }, &None);
+    assert_audited(&client, &mut audited, symbol_short!("wdpolicy"));
+    let id = client.queue_withdrawal(&issuer, &destination, &10_000_000, &None);
+    assert_audited(&client, &mut audited, symbol_short!("wdqueue"));
+    env.ledger().set_sequence_number(env.ledger().sequence() + 10);
+    client.execute_withdrawal(&issuer, &id, &None);
+    assert_audited(&client, &mut audited, symbol_short!("wdexec"));
+    let id = client.queue_withdrawal(&issuer, &destination, &10_000_000, &None);
+    assert_audited(&client, &mut audited, symbol_short!("wdqueue"));
+    client.cancel_withdrawal(&guardian, &id, &None);
+    assert_audited(&client, &mut audited, symbol_short!("wdcancel"));
+    client.remove_withdrawal_destination(&issuer, &destination, &None);
+    assert_audited(&client, &mut audited, symbol_short!("wdestrem"));
+    usdc_token_admin_client.mint(&contract_id, &1_000_000);
+    client.reconcile_usdc(&admin, &None);
+    assert_audited(&client, &mut audited, symbol_short!("reconcile"));
+
+    // Offering, housekeeping and capital actions
+    client.configure_offering(&issuer, &2_000, &3_000, &1_000_000_000, &2_000_000_000, &None);
+    assert_audited(&client, &mut audited, symbol_short!("offering"));
+    env.ledger().set_timestamp(3_000);
+    client.clear_offering(&issuer, &None);
+    assert_audited(&client, &mut audited, symbol_short!("offerrem"));
+    client.bump_instance_ttl(&admin, &None);
+    assert_audited(&client, &mut audited, symbol_short!("bumpinst"));
+    client.bump_user_ttls(&admin, &vec![&env, user1.clone()], &None);
+    assert_audited(&client, &mut audited, symbol_short!("bumpusers"));
+    let class_b = symbol_short!("class_b");
+    client.set_partition(&admin, &class_b, &PartitionRules {
+        locked_until: 0,
+        allowed_classes: vec![&env, InvestorClass::Accredited],
+    }, &None);
+    assert_audited(&client, &mut audited, symbol_short!("partition"));
+    client.change_partition(&admin, &user1, &symbol_short!("default"), &class_b, &1_000_000, &None);
+    assert_audited(&client, &mut audited, symbol_short!("partchg"));
+    client.clawback(&admin, &user1, &1_000_000, &None);
+    assert_audited(&client, &mut audited, symbol_short!("clawback"));
+    client.split(&admin, &2, &1, &None);
+    assert_audited(&client, &mut audited, symbol_short!("split"));
+
+    // Migration
+    let successor_id = env.register(MockSuccessor, ());
+    client.start_migration(&issuer, &successor_id, &1, &1, &None);
+    assert_audited(&client, &mut audited, symbol_short!("migrate"));
+    client.cancel_migration(&guardian, &None);
+    assert_audited(&client, &mut audited, symbol_short!("migcancel"));
+    client.start_migration(&issuer, &successor_id, &1, &1, &None);
+    assert_audited(&client, &mut audited, symbol_short!("migrate"));
+    env.ledger().set_sequence_number(client.migration().unwrap().executable_at);
+    client.migrate_to_successor(&admin, &vec![&env, user1.clone()], &None);
+    assert_audited(&client, &mut audited, symbol_short!("migrated"));
+
+    // Debt servicing on a second token
+    let debt_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let debt = SecurityTokenContractClient::new(&env, &debt_id);
+    debt.set_kyc_status(&admin, &user1, &true, &None);
+    debt.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    debt.set_transfer_restriction(&admin, &false, &None);
+    debt.transfer(&issuer, &user1, &250_000_000_000);
+    let mut audited = debt.audit_log_len();
+    let now = env.ledger().timestamp();
+    debt.set_debt_terms(&issuer, &DebtTerms {
+        face_value: 1_000_000_000,
+        coupon_bps: 500,
+        period: 1_000,
+        first_payment: now + 1_000,
+        maturity: now + 2_000,
+    }, &None);
+    assert_audited(&debt, &mut audited, symbol_short!("debt"));
+    usdc_token_admin_client.mint(&admin, &1_100_000_000_000_000);
+    env.ledger().set_timestamp(now + 1_000);
+    debt.fund_coupon(&admin, &None);
+    assert_audited(&debt, &mut audited, symbol_short!("coupon"));
+    env.ledger().set_timestamp(now + 2_000);
+    debt.fund_coupon(&admin, &None);
+    assert_audited(&debt, &mut audited, symbol_short!("coupon"));
+    debt.mature(&admin, &None);
+    assert_audited(&debt, &mut audited, symbol_short!("mature"));
+
+    // Wind-down on a third token
+    let wound_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let wound = SecurityTokenContractClient::new(&env, &wound_id);
+    let mut audited = wound.audit_log_len();
+    wound.start_wind_down(&admin, &1_000_000_000, &None);
+    assert_audited(&wound, &mut audited, symbol_short!("winddown"));
+}
+
+#[test]
+fn test_document_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    let memo = Symbol::new(&env, "offering_memorandum");
+    let appraisal = symbol_short!("appraisal");
+    let memo_v1 = BytesN::from_array(&env, &[1; 32]);
+    let memo_v2 = BytesN::from_array(&env, &[2; 32]);
+    let memo_uri = String::from_str(&env, "ipfs://memo");
+
+    client.set_document(&admin, &memo, &memo_uri, &memo_v1, &None);
+    client.set_document(&admin, &appraisal, &String::from_str(&env, "ipfs://appraisal"), &memo_v1, &None);
+    assert_eq!(client.all_documents(), vec![&env, memo.clone(), appraisal.clone()]);
+
+    // Updating a document replaces its hash without duplicating the name
+    client.set_document(&admin, &memo, &memo_uri, &memo_v2, &None);
+    assert_eq!(client.all_documents().len(), 2);
+    assert_eq!(
+        client.get_document(&memo),
+        Some(Document {
+            uri: memo_uri,
+            document_hash: memo_v2,
+            updated_at: env.ledger().timestamp(),
+        })
+    );
+
+    // Removed documents are gone
+    client.remove_document(&admin, &appraisal, &None);
+    assert_eq!(client.all_documents(), vec![&env, memo.clone()]);
+    assert_eq!(client.get_document(&appraisal), None);
+    assert_eq!(
+        client.try_remove_document(&admin, &appraisal, &None),
+        Err(Ok(Error::from_contract_error(77)))
+    );
+}
+
+#[test]
+fn test_document_registry_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    let hash = BytesN::from_array(&env, &[1; 32]);
+
+    // Only admins can register documents
+    assert_eq!(
+        client.try_set_document(&user1, &symbol_short!("memo"), &String::from_str(&env, "ipfs://memo"), &hash, &None),
+        Err(Ok(Error::from_contract_error(75)))
+    );
+
+    // Empty URIs are rejected
+    assert_eq!(
+        client.try_set_document(&admin, &symbol_short!("memo"), &String::from_str(&env, ""), &hash, &None),
+        Err(Ok(Error::from_contract_error(76)))
+    );
+}
+
+#[test]
+fn test_purchase_requires_accepted_terms() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    let agreement_v1 = BytesN::from_array(&env, &[1; 32]);
+    let agreement_v2 = BytesN::from_array(&env, &[2; 32]);
+    client.set_active_terms(&admin, &agreement_v1, &None);
+
+    // Purchase fails until the buyer accepts the active terms
+    assert_eq!(
+        client.try_purchase(&buyer, &buyer, &500_000_000, &None),
+        Err(Ok(Error::from_contract_error(79)))
+    );
+    client.accept_terms(&buyer, &agreement_v1);
+    assert_eq!(
+        client.terms_acceptance(&buyer),
+        Some(TermsAcceptance {
+            document_hash: agreement_v1.clone(),
+            accepted_at: env.ledger().timestamp(),
+        })
+    );
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+
+    // A new agreement version requires re-acceptance
+    client.set_active_terms(&admin, &agreement_v2, &None);
+    assert_eq!(
+        client.try_purchase(&buyer, &buyer, &500_000_000, &None),
+        Err(Ok(Error::from_contract_error(79)))
+    );
+    client.accept_terms(&buyer, &agreement_v2);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+    assert_eq!(client.balance(&buyer), 1_000_000_000);
+}
+
+#[test]
+fn test_clear_active_terms() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&buyer, &1_000_000_000);
+    client.set_kyc_status(&admin, &buyer, &true, &None);
+    client.set_compliance_status(&admin, &buyer, &ComplianceStatus::Approved, &None);
+
+    // Only admins manage the active terms
+    let agreement = BytesN::from_array(&env, &[1; 32]);
+    assert_eq!(
+        client.try_set_active_terms(&buyer, &agreement, &None),
+        Err(Ok(Error::from_contract_error(78)))
+    );
+    client.set_active_terms(&admin, &agreement, &None);
+    assert_eq!(client.active_terms(), Some(agreement));
+
+    // Once cleared, purchases no longer require acceptance
+    client.clear_active_terms(&admin, &None);
+    assert_eq!(client.active_terms(), None);
+    client.purchase(&buyer, &buyer, &500_000_000, &None);
+    assert_eq!(client.balance(&buyer), 500_000_000);
+}
+
+#[test]
+fn test_update_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    // Only the issuer can update metadata
+    assert_eq!(
+        client.try_update_metadata(&admin, &Some(String::from_str(&env, "Renamed")), &None, &None, &None),
+        Err(Ok(Error::from_contract_error(26)))
+    );
+
+    // Rebrand name and home domain, symbol unchanged
+    client.update_metadata(
+        &issuer,
+        &Some(String::from_str(&env, "Harbor Tower Token")),
+        &None,
+        &Some(String::from_str(&env, "harbortower.example")),
+        &None,
+    );
+    let metadata = client.get_metadata();
+    assert_eq!(metadata.name, String::from_str(&env, "Harbor Tower Token"));
+    assert_eq!(metadata.symbol, String::from_str(&env, "SCTY"));
+    assert_eq!(metadata.home_domain, String::from_str(&env, "harbortower.example"));
+
+    // Length limits from the constructor apply
+    assert_eq!(
+        client.try_update_metadata(&issuer, &None, &Some(String::from_str(&env, "THIRTEENCHARS")), &None, &None),
+        Err(Ok(Error::from_contract_error(80)))
+    );
+    assert_eq!(
+        client.try_update_metadata(&issuer, &None, &None, &Some(String::from_str(&env, "")), &None),
+        Err(Ok(Error::from_contract_error(80)))
+    );
+    assert_eq!(
+        client.try_update_metadata(&issuer, &None, &None, &None, &None),
+        Err(Ok(Error::from_contract_error(80)))
+    );
+}
+
+#[test]
+fn test_lock_metadata_field() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.lock_metadata_field(&issuer, &MetadataField::Symbol, &None);
+    assert_eq!(client.metadata_locks(), vec![&env, MetadataField::Symbol]);
+
+    // Locked fields cannot change, even alongside unlocked ones
+    assert_eq!(
+        client.try_update_metadata(
+            &issuer,
+            &Some(String::from_str(&env, "New Name")),
+            &Some(String::from_str(&env, "NEW")),
+            &None,
+            &None,
+        ),
+        Err(Ok(Error::from_contract_error(81)))
+    );
+    assert_eq!(client.get_metadata().name, String::from_str(&env, "Security Token"));
+
+    // Other fields stay mutable; locks are permanent
+    client.update_metadata(&issuer, &Some(String::from_str(&env, "New Name")), &None, &None, &None);
+    assert_eq!(client.get_metadata().name, String::from_str(&env, "New Name"));
+    assert_eq!(
+        client.try_lock_metadata_field(&issuer, &MetadataField::Symbol, &None),
+        Err(Ok(Error::from_contract_error(81)))
+    );
+}
+
+#[test]
+fn test_partition_transfer_and_class_rules() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved, &None);
+    client.set_investor_class(&admin, &user1, &InvestorClass::Accredited, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    // Class B units are only for accredited investors
+    let class_b = symbol_short!("class_b");
+    client.set_partition(&admin, &class_b, &PartitionRules {
+        locked_until: 0,
+        allowed_classes: vec![&env, InvestorClass::Accredited],
+    }, &None);
+    client.change_partition(&admin, &issuer, &symbol_short!("default"), &class_b, &1_000_000, &None);
+    assert_eq!(client.balance_of_by_partition(&issuer, &class_b), 1_000_000);
+    assert_eq!(client.balance_of_by_partition(&issuer, &symbol_short!("default")), 1_000_000_000_000 - 1_000_000);
+    assert_eq!(client.balance(&issuer), 1_000_000_000_000);
+
+    client.transfer_by_partition(&issuer, &class_b, &user1, &400_000);
+    assert_eq!(client.balance_of_by_partition(&user1, &class_b), 400_000);
+    assert_eq!(client.balance(&user1), 400_000);
+    assert_eq!(
+        client.try_transfer_by_partition(&issuer, &class_b, &user2, &100_000),
+        Err(Ok(Error::from_contract_error(86)))
+    );
+
+    // Partitioned units cannot leave through a plain transfer
+    assert_eq!(
+        client.try_transfer(&user1, &user2, &100_000),
+        Err(Ok(Error::from_contract_error(14)))
+    );
+    assert_eq!(
+        client.try_transfer_by_partition(&issuer, &class_b, &user1, &600_001),
+        Err(Ok(Error::from_contract_error(84)))
+    );
+
+    // The default partition behaves like a plain transfer
+    client.transfer_by_partition(&issuer, &symbol_short!("default"), &user2, &100_000);
+    assert_eq!(client.balance(&user2), 100_000);
+}
+
+#[test]
+fn test_locked_partition() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    // Locked tranche until the ledger reaches the unlock time
+    let locked = symbol_short!("locked");
+    let unlock_time = env.ledger().timestamp() + 1_000;
+    client.set_partition(&admin, &locked, &PartitionRules {
+        locked_until: unlock_time,
+        allowed_classes: Vec::new(&env),
+    }, &None);
+    client.change_partition(&admin, &issuer, &symbol_short!("default"), &locked, &500_000, &None);
+    assert_eq!(
+        client.try_transfer_by_partition(&issuer, &locked, &user1, &500_000),
+        Err(Ok(Error::from_contract_error(85)))
+    );
+
+    env.ledger().set_timestamp(unlock_time);
+    client.transfer_by_partition(&issuer, &locked, &user1, &500_000);
+    assert_eq!(client.balance_of_by_partition(&user1, &locked), 500_000);
+
+    // Unknown partitions and the default partition cannot be configured
+    assert_eq!(
+        client.try_transfer_by_partition(&issuer, &symbol_short!("unknown"), &user1, &1),
+        Err(Ok(Error::from_contract_error(83)))
+    );
+    assert_eq!(
+        client.try_set_partition(&admin, &symbol_short!("default"), &PartitionRules {
+            locked_until: 0,
+            allowed_classes: Vec::new(&env),
+        }, &None),
+        Err(Ok(Error::from_contract_error(83)))
+    );
+}
+
+#[test]
+fn test_clawback_drains_partitions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    let class_b = symbol_short!("class_b");
+    client.set_partition(&admin, &class_b, &PartitionRules {
+        locked_until: 0,
+        allowed_classes: Vec::new(&env),
+    }, &None);
+    client.transfer(&issuer, &user1, &300_000);
+    client.change_partition(&admin, &user1, &symbol_short!("default"), &class_b, &200_000, &None);
+
+    // Default units go first, then the class B units
+    client.clawback(&admin, &user1, &250_000, &None);
+    assert_eq!(client.balance(&user1), 50_000);
+    assert_eq!(client.balance_of_by_partition(&user1, &symbol_short!("default")), 0);
+    assert_eq!(client.balance_of_by_partition(&user1, &class_b), 50_000);
+}
+
+#[test]
+fn test_wind_down_and_final_redemption() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+    client.transfer(&issuer, &user1, &250_000_000_000);
+
+    // Redemption is only available after wind-down starts
+    assert_eq!(
+        client.try_redeem_final(&user1),
+        Err(Ok(Error::from_contract_error(89)))
+    );
+
+    // Property sold: admin deposits 2,000 USDC of proceeds
+    usdc_token_admin_client.mint(&admin, &20_000_000_000);
+    client.start_wind_down(&admin, &20_000_000_000, &None);
+    assert_eq!(usdc_token_client.balance(&contract_id), 20_000_000_000);
+    assert_eq!(client.usdc_reconciliation().surplus, 0);
+
+    // user1 holds 25% of supply
+    assert_eq!(client.redeem_final(&user1), 5_000_000_000);
+    assert_eq!(usdc_token_client.balance(&user1), 5_000_000_000);
+    assert_eq!(client.balance(&user1), 0);
+    assert_eq!(
+        client.try_redeem_final(&user1),
+        Err(Ok(Error::from_contract_error(90)))
+    );
+    assert!(!client.is_fully_retired());
+
+    // Issuer redeems the rest and the supply is retired
+    assert_eq!(client.redeem_final(&issuer), 15_000_000_000);
+    assert!(client.is_fully_retired());
+    assert_eq!(client.get_metadata().total_supply, 0);
+    let wind_down = client.wind_down().unwrap();
+    assert_eq!(wind_down.redeemed, 1_000_000_000_000);
+    assert_eq!(wind_down.paid, 20_000_000_000);
+    assert_eq!(client.holder_count(), 0);
+}
+
+#[test]
+fn test_wind_down_stops_transfers_and_purchases() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    usdc_token_admin_client.mint(&user1, &1_000_000_000);
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    // Only admins can start the wind-down
+    usdc_token_admin_client.mint(&admin, &1_000_000_000);
+    assert_eq!(
+        client.try_start_wind_down(&user1, &1_000_000_000, &None),
+        Err(Ok(Error::from_contract_error(87)))
+    );
+    client.start_wind_down(&admin, &1_000_000_000, &None);
+
+    assert_eq!(
+        client.try_transfer(&issuer, &user1, &100_000),
+        Err(Ok(Error::from_contract_error(88)))
+    );
+    assert_eq!(
+        client.try_purchase(&user1, &user1, &500_000_000, &None),
+        Err(Ok(Error::from_contract_error(88)))
+    );
+    assert_eq!(
+        client.try_start_wind_down(&admin, &1, &None),
+        Err(Ok(Error::from_contract_error(88)))
+    );
+}
+
+#[test]
+fn test_debt_coupons_and_maturity() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    // 100 USDC face value per token, 5% annual coupon paid semi-annually
+    let period = 15_768_000;
+    env.ledger().set_timestamp(1_000);
+    let terms = DebtTerms {
+        face_value: 1_000_000_000,
+        coupon_bps: 500,
+        period,
+        first_payment: 1_000 + period,
+        maturity: 1_000 + 2 * period,
+    };
+    client.set_debt_terms(&issuer, &terms, &None);
+    assert_eq!(client.debt_terms(), Some(terms));
+    client.transfer(&issuer, &user1, &250_000_000_000);
+
+    // Coupon cannot be funded before the payment date
+    usdc_token_admin_client.mint(&admin, &1_100_000_000_000_000);
+    assert_eq!(
+        client.try_fund_coupon(&admin, &None),
+        Err(Ok(Error::from_contract_error(94)))
+    );
+
+    // First coupon: 2.5 USDC per token on the 250,000 tokens outside the issuer's inventory
+    env.ledger().set_timestamp(1_000 + period);
+    assert_eq!(client.fund_coupon(&admin, &None), 6_250_000_000_000);
+    assert_eq!(client.accrued_coupon(&user1), 6_250_000_000_000);
+    assert_eq!(client.usdc_reconciliation().surplus, 0);
+
+    // Coupon accrued before a transfer stays with the seller
+    client.transfer(&user1, &issuer, &125_000_000_000);
+    assert_eq!(client.accrued_coupon(&user1), 6_250_000_000_000);
+
+    // Second coupon accrues on the reduced balance
+    env.ledger().set_timestamp(1_000 + 2 * period);
+    assert_eq!(client.fund_coupon(&admin, &None), 3_125_000_000_000);
+    assert_eq!(client.claim_coupon(&user1), 9_375_000_000_000);
+    assert_eq!(usdc_token_client.balance(&user1), 9_375_000_000_000);
+    assert_eq!(
+        client.try_claim_coupon(&user1),
+        Err(Ok(Error::from_contract_error(62)))
+    );
+    assert_eq!(client.coupon_state().unwrap().payments, 2);
+
+    // No further coupon dates remain before maturity
+    assert_eq!(
+        client.try_fund_coupon(&admin, &None),
+        Err(Ok(Error::from_contract_error(94)))
+    );
+
+    // Principal is deposited at maturity and redeemed pro-rata
+    assert_eq!(client.mature(&admin, &None), 1_000_000_000_000_000);
+    assert_eq!(client.redeem_final(&user1), 125_000_000_000_000);
+    assert_eq!(client.usdc_reconciliation().surplus, 0);
+}
+
+#[test]
+fn test_coupon_follows_holders_on_payment_date() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    // 100 USDC face value per token, 5% annual coupon paid semi-annually
+    let period = 15_768_000;
+    env.ledger().set_timestamp(1_000);
+    client.set_debt_terms(
+        &issuer,
+        &DebtTerms {
+            face_value: 1_000_000_000,
+            coupon_bps: 500,
+            period,
+            first_payment: 1_000 + period,
+            maturity: 1_000 + 2 * period,
+        },
+        &None,
+    );
+    client.transfer(&issuer, &user1, &250_000_000_000);
+
+    // After the payment date but before funding, user1 sells to user2 and the
+    // issuer sells inventory to user2
+    env.ledger().set_timestamp(1_000 + period + 100);
+    client.transfer(&user1, &user2, &250_000_000_000);
+    client.transfer(&issuer, &user2, &100_000_000_000);
+    assert_eq!(
+        client.try_claim_coupon(&user1),
+        Err(Ok(Error::from_contract_error(107)))
+    );
+
+    // Late funding covers only user1's holding on the date, not the issuer's inventory
+    usdc_token_admin_client.mint(&admin, &1_000_000_000_000_000);
+    assert_eq!(client.fund_coupon(&admin, &None), 6_250_000_000_000);
+    assert_eq!(client.accrued_coupon(&user1), 6_250_000_000_000);
+    assert_eq!(client.accrued_coupon(&user2), 0);
+    assert_eq!(client.accrued_coupon(&issuer), 0);
+    assert_eq!(client.claim_coupon(&user1), 6_250_000_000_000);
+    assert_eq!(
+        client.try_claim_coupon(&issuer),
+        Err(Ok(Error::from_contract_error(62)))
+    );
+    assert_eq!(client.usdc_reconciliation().surplus, 0);
+}
+
+#[test]
+fn test_debt_terms_validation_and_access() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+    client.transfer(&issuer, &user1, &100_000_000_000);
+
+    env.ledger().set_timestamp(1_000);
+    let terms = DebtTerms {
+        face_value: 1_000_000_000,
+        coupon_bps: 800,
+        period: 1_000,
+        first_payment: 2_000,
+        maturity: 3_000,
+    };
+
+    // Coupon functions require debt mode
+    assert_eq!(
+        client.try_fund_coupon(&admin, &None),
+        Err(Ok(Error::from_contract_error(93)))
+    );
+    assert_eq!(
+        client.try_mature(&admin, &None),
+        Err(Ok(Error::from_contract_error(93)))
+    );
+
+    // Only the issuer can set terms, and they must be consistent
+    assert_eq!(
+        client.try_set_debt_terms(&admin, &terms, &None),
+        Err(Ok(Error::from_contract_error(26)))
+    );
+    let mut invalid = terms.clone();
+    invalid.maturity = 1_500;
+    assert_eq!(
+        client.try_set_debt_terms(&issuer, &invalid, &None),
+        Err(Ok(Error::from_contract_error(92)))
+    );
+    client.set_debt_terms(&issuer, &terms, &None);
+
+    // Terms are fixed once set
+    assert_eq!(
+        client.try_set_debt_terms(&issuer, &terms, &None),
+        Err(Ok(Error::from_contract_error(92)))
+    );
+
+    // Only admins fund coupons
+    usdc_token_admin_client.mint(&admin, &10_000_000_000_000_000);
+    env.ledger().set_timestamp(2_000);
+    assert_eq!(
+        client.try_fund_coupon(&user1, &None),
+        Err(Ok(Error::from_contract_error(91)))
+    );
+
+    // Maturity requires every coupon date to be funded
+    env.ledger().set_timestamp(3_000);
+    assert_eq!(
+        client.try_mature(&admin, &None),
+        Err(Ok(Error::from_contract_error(95)))
+    );
+    client.fund_coupon(&admin, &None);
+    assert_eq!(client.coupon_state().unwrap().payments, 2);
+    client.mature(&admin, &None);
+    assert!(client.wind_down().is_some());
+}
+
+#[test]
+fn test_coupon_accrual_overflow_is_reported() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+    client.transfer(&issuer, &user1, &200_000);
+
+    env.ledger().set_timestamp(1_000);
+    client.set_debt_terms(
+        &issuer,
+        &DebtTerms {
+            face_value: 1_000_000_000,
+            coupon_bps: 500,
+            period: 1_000,
+            first_payment: 2_000,
+            maturity: 3_000,
+        },
+        &None,
+    );
+
+    // An index too large to apply to the holder's balance
+    env.as_contract(&contract_id, || {
+        env.storage().instance().set(
+            &COUPON_KEY,
+            &CouponState {
+                index: i128::MAX / 2,
+                payments: 0,
+                due: 0,
+                funded: 0,
+                claimed: 0,
+            },
+        );
+    });
+
+    // Overflow is reported instead of trapping, and the balance is left unchanged
+    assert_eq!(
+        client.try_accrued_coupon(&user1),
+        Err(Ok(Error::from_contract_error(16)))
+    );
+    assert_eq!(
+        client.try_transfer(&user1, &issuer, &100_000),
+        Err(Ok(Error::from_contract_error(16)))
+    );
+    assert_eq!(client.balance(&user1), 200_000);
+}
+
+#[test]
+fn test_split_and_reverse_split() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+    client.transfer(&issuer, &user1, &250_000_000_001);
+
+    // Only admins can split
+    assert_eq!(
+        client.try_split(&user1, &10, &1, &None),
+        Err(Ok(Error::from_contract_error(96)))
+    );
+    assert_eq!(
+        client.try_split(&admin, &3, &3, &None),
+        Err(Ok(Error::from_contract_error(97)))
+    );
+
+    // 10:1 split lowers the price and multiplies every balance
+    client.split(&admin, &10, &1, &None);
+    let metadata = client.get_metadata();
+    assert_eq!(metadata.total_supply, 10_000_000_000_000);
+    assert_eq!(metadata.usdc_price, 10_000);
+    assert_eq!(client.balance(&user1), 2_500_000_000_010);
+    assert_eq!(client.balance(&issuer), 7_499_999_999_990);
+
+    // Balances keep working after a lazy conversion
+    client.transfer(&user1, &issuer, &10);
+    assert_eq!(client.balance(&user1), 2_500_000_000_000);
+    assert_eq!(client.balance(&issuer), 7_500_000_000_000);
+
+    // 1:3 reverse split rounds each balance and the supply down
+    client.split(&admin, &1, &3, &None);
+    let metadata = client.get_metadata();
+    assert_eq!(metadata.total_supply, 3_333_333_333_333);
+    assert_eq!(metadata.usdc_price, 30_000);
+    assert_eq!(client.balance(&user1), 833_333_333_333);
+    assert_eq!(client.balance(&issuer), 2_500_000_000_000);
+    assert_eq!(client.splits().len(), 2);
+    assert_eq!(client.holder_count(), 2);
+}
+
+#[test]
+fn test_split_converts_partitions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    let class_b = symbol_short!("class_b");
+    client.set_partition(&admin, &class_b, &PartitionRules {
+        locked_until: 0,
+        allowed_classes: vec![&env],
+    }, &None);
+    client.change_partition(&admin, &issuer, &symbol_short!("default"), &class_b, &1_000_000, &None);
+
+    client.split(&admin, &2, &1, &None);
+    assert_eq!(client.balance_of_by_partition(&issuer, &class_b), 2_000_000);
+    assert_eq!(client.balance_of_by_partition(&issuer, &symbol_short!("default")), 2_000_000_000_000 - 2_000_000);
+
+    // Moving units after the split works in post-split units
+    client.change_partition(&admin, &issuer, &class_b, &symbol_short!("default"), &2_000_000, &None);
+    assert_eq!(client.balance_of_by_partition(&issuer, &class_b), 0);
+    assert_eq!(client.balance(&issuer), 2_000_000_000_000);
+
+    // Debt notes cannot be split
+    env.ledger().set_timestamp(1_000);
+    client.set_debt_terms(&issuer, &DebtTerms {
+        face_value: 1_000_000_000,
+        coupon_bps: 500,
+        period: 1_000,
+        first_payment: 2_000,
+        maturity: 3_000,
+    }, &None);
+    assert_eq!(
+        client.try_split(&admin, &2, &1, &None),
+        Err(Ok(Error::from_contract_error(97)))
+    );
+}
+
+#[test]
+fn test_reverse_split_supply_matches_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, usdc_token_admin_client) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+    client.transfer(&issuer, &user1, &5);
+    client.transfer(&issuer, &user2, &5);
+
+    // 1:3 reverse split: 5 units become 1 for each holder
+    client.split(&admin, &1, &3, &None);
+    assert_eq!(client.balance(&user1), 1);
+    assert_eq!(client.balance(&user2), 1);
+    assert_eq!(client.balance(&issuer), 333_333_333_330);
+    assert_eq!(client.get_metadata().total_supply, 333_333_333_333);
+
+    // Converting a balance burns the rounding dust of the unconverted pool
+    assert_eq!(client.convert_balance(&user1), 1);
+    assert_eq!(client.get_metadata().total_supply, 333_333_333_332);
+    client.transfer(&user2, &user1, &1);
+    client.convert_balance(&issuer);
+    assert_eq!(
+        client.get_metadata().total_supply,
+        client.balance(&issuer) + client.balance(&user1) + client.balance(&user2)
+    );
+
+    // Every holder redeems and the proceeds are paid out in full
+    usdc_token_admin_client.mint(&admin, &1_000_000_000);
+    client.start_wind_down(&admin, &1_000_000_000, &None);
+    client.redeem_final(&user1);
+    client.redeem_final(&issuer);
+    assert!(client.is_fully_retired());
+    assert_eq!(client.get_metadata().total_supply, 0);
+    assert_eq!(usdc_token_client.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_migrate_to_successor() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_kyc_status(&admin, &user2, &true, &None);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+    client.transfer(&issuer, &user1, &250_000_000_000);
+    client.transfer(&issuer, &user2, &150_000_000_000);
+    client.set_compliance_status(&admin, &user2, &ComplianceStatus::Suspended, &None);
+
+    // Successor issues 2 units per old unit
+    let successor_id = env.register(MockSuccessor, ());
+    let successor = MockSuccessorClient::new(&env, &successor_id);
+    assert_eq!(
+        client.try_start_migration(&user1, &successor_id, &2, &1, &None),
+        Err(Ok(Error::from_contract_error(26)))
+    );
+    client.start_migration(&issuer, &successor_id, &2, &1, &None);
+
+    // Transfers stop once migration starts
+    assert_eq!(
+        client.try_transfer(&user1, &issuer, &1),
+        Err(Ok(Error::from_contract_error(100)))
+    );
+
+    // Holders cannot migrate until the timelock has passed
+    assert_eq!(
+        client.try_migrate_to_successor(&user1, &vec![&env, user1.clone()], &None),
+        Err(Ok(Error::from_contract_error(105)))
+    );
+    env.ledger().set_sequence_number(client.migration().unwrap().executable_at);
+
+    // Holders can only migrate themselves
+    assert_eq!(
+        client.try_migrate_to_successor(&user1, &vec![&env, user2.clone()], &None),
+        Err(Ok(Error::from_contract_error(98)))
+    );
+    assert_eq!(client.migrate_to_successor(&user1, &vec![&env, user1.clone()], &None), 500_000_000_000);
+    assert_eq!(client.balance(&user1), 0);
+    assert_eq!(
+        successor.minted(&user1),
+        Some((500_000_000_000, true, ComplianceStatus::Approved))
+    );
+    assert_eq!(
+        client.try_migrate_to_successor(&user1, &vec![&env, user1.clone()], &None),
+        Err(Ok(Error::from_contract_error(102)))
+    );
+
+    // Admin migrates the rest in a batch, carrying the suspension over
+    client.migrate_to_successor(&admin, &vec![&env, issuer.clone(), user2.clone()], &None);
+    assert_eq!(
+        successor.minted(&user2),
+        Some((300_000_000_000, true, ComplianceStatus::Suspended))
+    );
+    assert!(client.is_migration_complete());
+    let migration = client.migration().unwrap();
+    assert_eq!(migration.migrated, migration.supply);
+    assert_eq!(migration.issued, 2_000_000_000_000);
+    assert_eq!(client.holder_count(), 0);
+}
+
+#[test]
+fn test_migrate_max_batch_fits_write_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+    client.set_transfer_restriction(&admin, &false, &None);
+
+    let mut records = Vec::new(&env);
+    let mut holders = Vec::new(&env);
+    for _ in 0..MAX_MIGRATION_BATCH_SIZE + 1 {
+        let holder = Address::generate(&env);
+        records.push_back(OnboardingRecord {
+            address: holder.clone(),
+            kyc_verified: true,
+            compliance: ComplianceStatus::Approved,
+            jurisdiction: symbol_short!("GB"),
+        });
+        holders.push_back(holder);
+    }
+    client.onboard_investors(&admin, &records, &None);
+    for holder in holders.iter() {
+        client.transfer(&issuer, &holder, &100_000);
+    }
+
+    // A split leaves every holder with a stored balance to convert
+    client.split(&admin, &2, &1, &None);
+    let successor_id = env.register(MockSuccessor, ());
+    client.start_migration(&issuer, &successor_id, &1, &1, &None);
+    env.ledger().set_sequence_number(client.migration().unwrap().executable_at);
+
+    // One holder over the cap is rejected
+    assert_eq!(
+        client.try_migrate_to_successor(&admin, &holders, &None),
+        Err(Ok(Error::from_contract_error(74)))
+    );
+
+    // A full batch stays within the per-transaction write limit
+    holders.pop_back();
+    client.migrate_to_successor(&admin, &holders, &None);
+    assert!(env.cost_estimate().resources().write_entries <= TX_WRITE_ENTRY_LIMIT);
+    for holder in holders.iter() {
+        assert_eq!(client.balance(&holder), 0);
+    }
+}
+
+#[test]
+fn test_migration_requires_start() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    assert_eq!(
+        client.try_migrate_to_successor(&issuer, &vec![&env, issuer.clone()], &None),
+        Err(Ok(Error::from_contract_error(101)))
+    );
+    assert!(!client.is_migration_complete());
+
+    // Ratio must be non-zero and the successor must be another contract
+    let successor_id = env.register(MockSuccessor, ());
+    assert_eq!(
+        client.try_start_migration(&issuer, &successor_id, &0, &1, &None),
+        Err(Ok(Error::from_contract_error(99)))
+    );
+    assert_eq!(
+        client.try_start_migration(&issuer, &contract_id, &1, &1, &None),
+        Err(Ok(Error::from_contract_error(99)))
+    );
+
+    // Migration cannot be started twice
+    client.start_migration(&issuer, &successor_id, &1, &1, &None);
+    assert_eq!(
+        client.try_start_migration(&issuer, &successor_id, &1, &1, &None),
+        Err(Ok(Error::from_contract_error(100)))
+    );
+}
+
+#[test]
+fn test_migration_issuer_only_and_cancellable() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let issuer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let user1 = Address::generate(&env);
+
+    // Setup test USDC token contract
+    let (usdc_token_client, _) = create_token_contract(&env, &admin);
+
+    // Initialize token via constructor
+    let contract_id = create_security_token(&env, &issuer, &admin, &usdc_token_client.address);
+    let client = SecurityTokenContractClient::new(&env, &contract_id);
+
+    client.set_kyc_status(&admin, &user1, &true, &None);
+    client.set_compliance_status(&admin, &user1, &ComplianceStatus::Approved, &None);
+    client.set_transfer_restriction(&admin, &false, &None);
+    client.transfer(&issuer, &user1, &100_000);
+    client.set_guardian(&issuer, &guardian, &None);
+
+    // An ordinary admin cannot register a successor
+    let successor_id = env.register(MockSuccessor, ());
+    assert_eq!(
+        client.try_start_migration(&admin, &successor_id, &1, &1, &None),
+        Err(Ok(Error::from_contract_error(26)))
+    );
+
+    // The guardian cancels a migration during the timelock and transfers resume
+    client.start_migration(&issuer, &successor_id, &1, &1, &None);
+    assert_eq!(
+        client.try_cancel_migration(&admin, &None),
+        Err(Ok(Error::from_contract_error(65)))
+    );
+    client.cancel_migration(&guardian, &None);
+    assert_eq!(client.migration(), None);
+    client.transfer(&user1, &issuer, &50_000);
+
+    // Once a holder has migrated the migration can no longer be cancelled
+    client.start_migration(&issuer, &successor_id, &1, &1, &None);
+    env.ledger().set_sequence_number(client.migration().unwrap().executable_at);
+    client.migrate_to_successor(&user1, &vec![&env, user1.clone()], &None);
+    assert_eq!(
+        client.try_cancel_migration(&issuer, &None),
+        Err(Ok(Error::from_contract_error(106)))
+    );
+    assert_eq!(
+        client.try_cancel_migration(&guardian, &None),
+        Err(Ok(Error::from_contract_error(106)))
+    );
+}